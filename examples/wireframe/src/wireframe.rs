@@ -1,6 +1,7 @@
 
 use super::camera::*;
 use ewgpu::*;
+use std::sync::Arc;
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default, bytemuck::Pod, bytemuck::Zeroable)]
@@ -52,7 +53,7 @@ pub struct GPUWireframe{
 }
 
 impl GPUWireframe{
-    pub fn new(device: &wgpu::Device, vertices: &[WireframeVert], indices: &[u32], width: f32) -> Self{
+    pub fn new(device: &Arc<Device>, vertices: &[WireframeVert], indices: &[u32], width: f32) -> Self{
 
         let line = (
             BufferBuilder::new()
@@ -88,7 +89,7 @@ pub struct WireframeRenderer{
 }
 
 impl WireframeRenderer{
-    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self{
+    pub fn new(device: &Arc<Device>, format: wgpu::TextureFormat) -> Self{
 
         let line_layout = pipeline_layout!(device,
             bind_groups: {
@@ -163,7 +164,7 @@ impl WireframeRenderer{
 
             let mut cpass_ppl = cpass.set_pipeline(&self.line_cppl);
 
-            cpass_ppl.set_push_const(0, camera);
+            cpass_ppl.set_push_const(0, camera).unwrap();
             cpass_ppl.set_bind_group(0, &wireframe.line, &[]);
             cpass_ppl.set_bind_group(1, &wireframe.mesh, &[]);
             cpass_ppl.set_bind_group(2, &self.width, &[]);
@@ -180,7 +181,7 @@ impl WireframeRenderer{
             let mut rpass_ppl = rpass.set_pipeline(&self.mesh_rppl);
             rpass_ppl.set_vertex_buffer(0, wireframe.mesh.1.slice(..));
             rpass_ppl.set_index_buffer(wireframe.mesh.0.slice(..));
-            rpass_ppl.draw_indexed(0..wireframe.mesh.0.len() as u32, 0, 0..1);
+            rpass_ppl.draw_indexed(.., 0, 0..1).unwrap();
         }
     }
 
@@ -8,27 +8,78 @@ pub fn generate_bind_group_content(ast: syn::DeriveInput) -> proc_macro2::TokenS
 
         let mut entries = Vec::<proc_macro2::TokenStream>::new();
         let mut resources = Vec::<proc_macro2::TokenStream>::new();
+        let mut resource_ids = Vec::<proc_macro2::TokenStream>::new();
 
-        for field in fields{
-            entries.push(generate_bind_group_entry(&field));
-            resources.push(generate_bind_group_content_resource(&field));
+        // Explicit binding indices have to be unique.
+        let mut bindings = std::collections::HashMap::<u32, syn::Ident>::new();
+        for field in fields.iter(){
+            if let Some(binding) = field_binding(field){
+                let index = binding.base10_parse::<u32>().expect("Expected an integer binding index");
+                let ident = field.ident.clone().expect("Tuple structs are not supported");
+                if let Some(other) = bindings.insert(index, ident.clone()){
+                    return syn::Error::new_spanned(
+                        binding,
+                        format!("Binding index {} is already used by {}", index, other)
+                    ).to_compile_error();
+                }
+            }
+        }
+
+        let field_count = fields.len();
+        let mut field_specs = Vec::<proc_macro2::TokenStream>::new();
+        for (index, field) in fields.iter().enumerate(){
+            let ty = &field.ty;
+            let binding = match field_binding(field){
+                Some(binding) => quote!{Some(#binding)},
+                None => quote!{None},
+            };
+            field_specs.push(quote!{(#binding, <#ty as BindGroupContent>::BINDING_COUNT)});
+
+            entries.push(generate_bind_group_entry(field, index));
+            resources.push(generate_bind_group_content_resource(field));
+            resource_ids.push(generate_bind_group_content_resource_id(field));
         }
 
         let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
 
+        // Non generic structs are checked where they are defined, generic ones when their
+        // entries are instantiated.
+        let check = if ast.generics.params.is_empty(){
+            quote!{
+                const _: u32 = #ident::__FIELD_BINDINGS.1;
+            }
+        } else{
+            quote!{}
+        };
+
         let output = quote!{
+            impl #impl_generics #ident #ty_generics #where_clause{
+                #[doc(hidden)]
+                const __FIELD_BINDINGS: ([u32; #field_count], u32) = field_bindings([#(#field_specs),*]);
+            }
+
+            #check
+
             impl #impl_generics BindGroupContent for #ident #ty_generics #where_clause{
+                const BINDING_COUNT: u32 = Self::__FIELD_BINDINGS.1;
+
                 fn entries(visibility: Option<wgpu::ShaderStages>) -> Vec<BindGroupLayoutEntry>{
                     let mut ret = Vec::new();
                     #(#entries)*
                     ret
                 }
 
-                fn resources<'br>(&'br self) -> Vec<wgpu::BindingResource<'br>>{
+                fn resources<'br>(&'br self) -> Vec<BindingResource<'br>>{
                     let mut ret = Vec::new();
                     #(#resources)*
                     ret
                 }
+
+                fn resource_ids(&self) -> Vec<ResourceId>{
+                    let mut ret = Vec::new();
+                    #(#resource_ids)*
+                    ret
+                }
             }
         };
 
@@ -38,78 +89,128 @@ pub fn generate_bind_group_content(ast: syn::DeriveInput) -> proc_macro2::TokenS
     panic!("Data type not supported");
 }
 
-fn parse_meta_own(attr: &syn::Attribute) -> syn::Meta {
-    fn clone_ident_segment(segment: &syn::PathSegment) -> syn::PathSegment {
-        syn::PathSegment {
-            ident: segment.ident.clone(),
-            arguments: syn::PathArguments::None,
-        }
-    }
-
-    let path = syn::Path {
-        leading_colon: attr
-            .path
-            .leading_colon
-            .as_ref()
-            .map(|colon| syn::Token![::](colon.spans)),
-            segments: attr
-                .path
-                .segments
-                .pairs()
-                .map(|pair| match pair {
-                    syn::punctuated::Pair::Punctuated(seg, punct) => {
-                        syn::punctuated::Pair::Punctuated(clone_ident_segment(seg), syn::Token![::](punct.spans))
-                    }
-                    syn::punctuated::Pair::End(seg) => syn::punctuated::Pair::End(clone_ident_segment(seg)),
-                })
-        .collect(),
-    };
-    todo!()
-}
-
-pub fn generate_bind_group_entry(field: &syn::Field) -> proc_macro2::TokenStream{
-    let visibility = field.attrs.iter().find(|a|{
-        a.path.is_ident("visibility")
-    });
-    let ty = &field.ty;
-
-    match visibility{
-        Some(visibility) => {
-            let visibility: proc_macro2::TokenStream = visibility.parse_meta().map(|m|{
-                match m{
-                    syn::Meta::NameValue(n) => {
-                        if let syn::Lit::Str(i) = n.lit{
-                            let content: syn::Expr = i.parse().expect("Error not an Ident");
-                            quote!{
-                                #content
-                            }
-                        }
-                        else{
-                            panic!("Invalid literal provided");
-                        }
-                    },
-                    _ => quote!{wgpu::ShaderStages::all()},
-                }
-            }).unwrap_or_else(|_|{
+fn generate_visibility(attr: &syn::Attribute) -> proc_macro2::TokenStream{
+    match attr.parse_meta(){
+        // #[visibility = "wgpu::ShaderStages::FRAGMENT"]
+        Ok(syn::Meta::NameValue(n)) => {
+            if let syn::Lit::Str(i) = n.lit{
+                let content: syn::Expr = i.parse().expect("Error not an Ident");
                 quote!{
-                    wgpu::ShaderStages::all()
+                    #content
                 }
-            });
-            quote!{
-                ret.append(&mut <#ty>::entries(Some(#visibility)));
+            }
+            else{
+                panic!("Invalid literal provided");
             }
         },
-        None => {
+        // #[visibility(VERTEX | FRAGMENT)]
+        Ok(syn::Meta::List(_)) | Err(_) => {
+            let stages = attr.parse_args_with(
+                syn::punctuated::Punctuated::<syn::Ident, syn::Token![|]>::parse_separated_nonempty
+            ).expect("Expected shader stages separated by |");
+            let stages = stages.iter();
             quote!{
-                ret.append(&mut <#ty>::entries(None));
+                #(wgpu::ShaderStages::#stages)|*
             }
+        },
+        _ => quote!{wgpu::ShaderStages::all()},
+    }
+}
+
+pub fn generate_bind_group_entry(field: &syn::Field, index: usize) -> proc_macro2::TokenStream{
+    let ty = &field.ty;
+
+    let mut visibility = quote!{visibility};
+    let mut modifiers = Vec::<proc_macro2::TokenStream>::new();
+
+    for attr in field.attrs.iter(){
+        if attr.path.is_ident("visibility"){
+            let stages = generate_visibility(attr);
+            visibility = quote!{Some(#stages)};
+        }
+        else if attr.path.is_ident("read_only"){
+            modifiers.push(quote!{.read_only()});
         }
+        else if attr.path.is_ident("uniform"){
+            modifiers.push(quote!{.uniform()});
+        }
+        else if attr.path.is_ident("dynamic_offset"){
+            modifiers.push(quote!{.dynamic_offset()});
+        }
+        else if attr.path.is_ident("min_binding_size"){
+            match attr.parse_meta(){
+                // #[min_binding_size = 16]
+                Ok(syn::Meta::NameValue(n)) => {
+                    let size = n.lit;
+                    modifiers.push(quote!{.min_binding_size(#size)});
+                },
+                // #[min_binding_size] uses the size of one element.
+                _ => {
+                    modifiers.push(quote!{.min_binding_size(<#ty as BindingSize>::binding_size())});
+                },
+            }
+        }
+    }
+
+    // The entries of the field are placed at consecutive indices starting at the binding index
+    // assigned to the field. Entries of nested content keep their offsets.
+    quote!{
+        ret.extend(<#ty>::entries(#visibility).into_iter().enumerate()
+            .map(|(i, entry)|{
+                let binding = Self::__FIELD_BINDINGS.0[#index] + entry.binding.unwrap_or(i as u32);
+                entry #(#modifiers)* .with_binding(binding)
+            }));
     }
 }
 
+///
+/// Returns the literal of a #[binding = N] attribute.
+///
+fn field_binding(field: &syn::Field) -> Option<syn::LitInt>{
+    let attr = field.attrs.iter().find(|a| a.path.is_ident("binding"))?;
+    match attr.parse_meta(){
+        Ok(syn::Meta::NameValue(syn::MetaNameValue{lit: syn::Lit::Int(binding), ..})) => Some(binding),
+        _ => panic!("Expected a binding attribute of the form #[binding = N]"),
+    }
+}
+
+///
+/// Returns the size of a dynamic offset binding, the min_binding_size or the size of one element.
+///
+fn dynamic_binding_size(field: &syn::Field) -> Option<proc_macro2::TokenStream>{
+    field.attrs.iter().find(|attr| attr.path.is_ident("dynamic_offset"))?;
+    let ty = &field.ty;
+
+    let min_binding_size = field.attrs.iter()
+        .find(|attr| attr.path.is_ident("min_binding_size"))
+        .and_then(|attr| match attr.parse_meta(){
+            Ok(syn::Meta::NameValue(n)) => Some(n.lit),
+            _ => None,
+        });
+    Some(match min_binding_size{
+        Some(size) => quote!{#size},
+        None => quote!{<#ty as BindingSize>::binding_size()},
+    })
+}
+
 pub fn generate_bind_group_content_resource(field: &syn::Field) -> proc_macro2::TokenStream{
+    let ident = &field.ident;
+    match dynamic_binding_size(field){
+        // Bindings with a dynamic offset only cover one element so that the offset can move them
+        // through the buffer.
+        Some(size) => quote!{
+            ret.extend(self.#ident.resources().into_iter()
+                .map(|resource| dynamic_binding_resource(resource, #size)));
+        },
+        None => quote!{
+            ret.append(&mut self.#ident.resources());
+        },
+    }
+}
+
+pub fn generate_bind_group_content_resource_id(field: &syn::Field) -> proc_macro2::TokenStream{
     let ident = &field.ident;
     quote!{
-        ret.append(&mut self.#ident.resources());
+        ret.append(&mut self.#ident.resource_ids());
     }
 }
@@ -119,13 +119,46 @@ pub fn derive_vert(tokens: TokenStream) -> TokenStream{
 /// }
 /// ```
 ///
-#[proc_macro_derive(BindGroupContent, attributes(visibility))]
+/// Fields can be configured with the attributes visibility, read_only, uniform, dynamic_offset
+/// and min_binding_size.
+/// Fields without a visibility attribute use the visibility passed to entries.
+/// Fields with dynamic_offset are bound with a size of min_binding_size, or one element if it is
+/// not set, so that the offset can move the binding through the buffer.
+///
+/// The binding attribute places the entries of a field at explicit binding indices.
+/// Fields without it follow the previous entry so bindings can be sparse.
+/// Duplicate binding indices result in a compile error.
+///
+/// ```
+/// #[derive(BindGroupContent)]
+/// struct TestBindGroupContent{
+///     #[binding = 2]
+///     #[visibility(FRAGMENT)]
+///     #[read_only]
+///     indices: Buffer<u32>,
+///     #[visibility(VERTEX | FRAGMENT)]
+///     #[uniform]
+///     #[dynamic_offset]
+///     #[min_binding_size]
+///     camera: Buffer<[f32; 16]>,
+/// }
+/// ```
+///
+#[proc_macro_derive(BindGroupContent, attributes(binding, visibility, read_only, uniform, dynamic_offset, min_binding_size))]
 pub fn derive_bind_group_content(tokens: TokenStream) -> TokenStream{
     let ast: syn::DeriveInput = syn::parse(tokens).unwrap();
 
     generate_bind_group_content(ast).into()
 }
 
+///
+/// Creates a PipelineLayout with named bind group and push constant slots.
+///
+/// Used as an expression it returns a NamedPipelineLayout whose slot types cannot be named.
+/// Used as an item, `pipeline_layout!{pub struct Name{...}}`, it declares the slot types
+/// NameSlots and NamePushSlots and the alias Name, which is created with Name::new(device).
+/// See NamedPipelineLayout for examples.
+///
 #[proc_macro]
 pub fn pipeline_layout(tokens: TokenStream) -> TokenStream{
     generate_pipeline_layout(tokens)
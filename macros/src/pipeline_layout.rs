@@ -1,4 +1,4 @@
-use quote::quote;
+use quote::{quote, format_ident};
 use proc_macro::TokenStream;
 use syn::*;
 use syn::parse::Parse;
@@ -18,21 +18,10 @@ impl Slot{
         };
 
         let ty = &self.ty;
-        let ty = quote!(#ty);
-
-        let vis = match &self.vis{
-            Some(vis) => quote!(#vis),
-            None => quote!(wgpu::ShaderStages::all()),
-        };
-        quote!{&<#ty>::create_bind_group_layout(#device, #name).layout,}
+        quote!{&<#ty>::create_bind_group_layout(#device, #name),}
     }
 
     fn quote_push_const(&self) -> proc_macro2::TokenStream{
-        let _name = match &self.name{
-            Some(name) => quote!(Some(std::stringify!(#name))),
-            None => quote!(None),
-        };
-
         let ty = &self.ty;
         let ty = quote!(#ty);
 
@@ -179,6 +168,14 @@ impl Parse for PipelineLayout{
             let _c: Token!(,) = input.parse()?;
         }
 
+        // The layouts of bind groups are shared with the BindGroups created by into_bound, which
+        // use the visibility of the BindGroupContent.
+        if let Some(bind_groups) = &bind_groups{
+            if let Some(vis) = bind_groups.slots.iter().find_map(|slot| slot.vis.as_ref()){
+                return Err(Error::new_spanned(vis, "visibility is not supported for bind group slots"));
+            }
+        }
+
         Ok(Self{
             bind_groups,
             push_constants,
@@ -186,43 +183,184 @@ impl Parse for PipelineLayout{
     }
 }
 
-struct MacroInput{
-    device: Expr,
-    pipeline_layout: PipelineLayout,
+enum MacroInput{
+    // pipeline_layout!(&device, bind_groups: {...}, push_constants: {...})
+    Expr{
+        device: Expr,
+        pipeline_layout: PipelineLayout,
+    },
+    // pipeline_layout!{pub struct Name{bind_groups: {...}, push_constants: {...}}}
+    Item{
+        vis: Visibility,
+        name: Ident,
+        pipeline_layout: PipelineLayout,
+    },
 }
 
 impl Parse for MacroInput{
     fn parse(input: parse::ParseStream) -> Result<Self> {
+        if input.peek(Token!(struct)) || input.peek(Token!(pub)){
+            let vis = input.parse()?;
+            let _struct: Token!(struct) = input.parse()?;
+            let name = input.parse()?;
+
+            let content;
+            let _brace: token::Brace = braced!(content in input);
+            let pipeline_layout = content.parse()?;
+
+            return Ok(Self::Item{
+                vis,
+                name,
+                pipeline_layout,
+            });
+        }
+
         let device = input.parse()?;
 
         let _c: Token!(,) = input.parse()?;
 
         let pipeline_layout = input.parse()?;
 
-        Ok(Self{
+        Ok(Self::Expr{
             device,
             pipeline_layout,
         })
     }
 }
 
-impl MacroInput{
-    fn quote(&self) -> proc_macro2::TokenStream{
-        let device = &self.device;
-        let device_ts = quote!{#device};
+///
+/// The tokens generated for the named slots of a PipelineLayout.
+///
+struct SlotTokens{
+    slot_fields: Vec<proc_macro2::TokenStream>,
+    slot_values: Vec<proc_macro2::TokenStream>,
+    push_fields: Vec<proc_macro2::TokenStream>,
+    push_values: Vec<proc_macro2::TokenStream>,
+}
 
-        let bind_groups = match &self.pipeline_layout.bind_groups{
-            Some(bind_groups) => bind_groups.quote_bind_group(&device_ts),
+impl PipelineLayout{
+    fn quote_layout(&self, device: &proc_macro2::TokenStream) -> proc_macro2::TokenStream{
+        let bind_groups = match &self.bind_groups{
+            Some(bind_groups) => bind_groups.quote_bind_group(device),
             None => quote!{&[]},
         };
 
-        let push_constants = match &self.pipeline_layout.push_constants{
+        let push_constants = match &self.push_constants{
             Some(push_constants) => push_constants.quote_push_const(),
             None => quote!{&[]},
         };
 
-        quote!{
-            PipelineLayout::new(#device, #bind_groups, #push_constants, None)
+        quote!{PipelineLayout::from_layouts(#device, #bind_groups, #push_constants, None)}
+    }
+
+    fn slot_tokens(&self) -> SlotTokens{
+        // Named bind group slots.
+        let mut slot_fields = Vec::new();
+        let mut slot_values = Vec::new();
+        if let Some(bind_groups) = &self.bind_groups{
+            for (i, slot) in bind_groups.slots.iter().enumerate(){
+                if let Some(name) = &slot.name{
+                    let ty = &slot.ty;
+                    let index = i as u32;
+                    slot_fields.push(quote!{pub #name: BindGroupSlot<<#ty as SlotContent>::Content>,});
+                    slot_values.push(quote!{#name: BindGroupSlot::new(#index),});
+                }
+            }
+        }
+
+        // Named push constant slots.
+        // The layout is created from the same types in the same order, so the push constant at
+        // index i always holds the type of slot i.
+        let mut push_fields = Vec::new();
+        let mut push_values = Vec::new();
+        if let Some(push_constants) = &self.push_constants{
+            for (i, slot) in push_constants.slots.iter().enumerate(){
+                if let Some(name) = &slot.name{
+                    let ty = &slot.ty;
+                    push_fields.push(quote!{pub #name: PushConstantSlot<#ty>,});
+                    push_values.push(quote!{#name: PushConstantSlot::new_unchecked(#i),});
+                }
+            }
+        }
+
+        SlotTokens{
+            slot_fields,
+            slot_values,
+            push_fields,
+            push_values,
+        }
+    }
+}
+
+impl MacroInput{
+    fn quote(&self) -> proc_macro2::TokenStream{
+        match self{
+            Self::Expr{device, pipeline_layout} => {
+                let layout = pipeline_layout.quote_layout(&quote!{#device});
+                let SlotTokens{slot_fields, slot_values, push_fields, push_values} = pipeline_layout.slot_tokens();
+
+                quote!{
+                    {
+                        #[allow(dead_code)]
+                        #[derive(Clone, Copy)]
+                        pub struct Slots{
+                            #(#slot_fields)*
+                        }
+                        #[allow(dead_code)]
+                        #[derive(Clone, Copy)]
+                        pub struct PushSlots{
+                            #(#push_fields)*
+                        }
+
+                        NamedPipelineLayout{
+                            layout: #layout,
+                            slots: Slots{
+                                #(#slot_values)*
+                            },
+                            push: PushSlots{
+                                #(#push_values)*
+                            },
+                        }
+                    }
+                }
+            },
+            Self::Item{vis, name, pipeline_layout} => {
+                let layout = pipeline_layout.quote_layout(&quote!{device});
+                let SlotTokens{slot_fields, slot_values, push_fields, push_values} = pipeline_layout.slot_tokens();
+                let slots = format_ident!("{}Slots", name);
+                let push_slots = format_ident!("{}PushSlots", name);
+
+                quote!{
+                    #[allow(dead_code)]
+                    #[derive(Clone, Copy)]
+                    #vis struct #slots{
+                        #(#slot_fields)*
+                    }
+                    #[allow(dead_code)]
+                    #[derive(Clone, Copy)]
+                    #vis struct #push_slots{
+                        #(#push_fields)*
+                    }
+
+                    #vis type #name = NamedPipelineLayout<#slots, #push_slots>;
+
+                    impl PipelineLayoutSlots for #slots{
+                        type PushSlots = #push_slots;
+
+                        fn create_layout(device: &Device) -> NamedPipelineLayout<Self, Self::PushSlots>{
+                            NamedPipelineLayout{
+                                layout: #layout,
+                                slots: #slots{
+                                    #(#slot_values)*
+                                },
+                                push: #push_slots{
+                                    #(#push_values)*
+                                },
+                            }
+                        }
+                    }
+                }
+            },
         }
     }
 }
@@ -0,0 +1,58 @@
+use std::marker::PhantomData;
+use anyhow::Result;
+use crate::*;
+use super::{PrefixSum, StorageKernel};
+
+///
+/// Stream compaction that keeps the elements whose flag is not zero.
+///
+/// The kept elements are written to the front of the output in their original order and the
+/// number of kept elements is written to a count buffer.
+/// Elements are copied as 32 bit words, so the size of T has to be a multiple of four.
+///
+pub struct Compact<T: bytemuck::Pod>{
+    flags: StorageKernel,
+    scatter: StorageKernel,
+    scan: PrefixSum<u32>,
+    _ty: PhantomData<T>,
+}
+
+impl<T: bytemuck::Pod> Compact<T>{
+    pub fn new(device: &Device) -> Result<Self>{
+        let size = std::mem::size_of::<T>();
+        if size == 0 || !size.is_multiple_of(4){
+            return Err(anyhow::anyhow!("Compact requires elements with a size that is a multiple of 4 bytes, got {}", size));
+        }
+        let words = (size / 4).to_string();
+
+        Ok(Self{
+            flags: StorageKernel::new(device, include_str!("shaders/compact_flags.glsl"), &[], 2, Some("compact_flags"))?,
+            scatter: StorageKernel::new(device, include_str!("shaders/compact_scatter.glsl"), &[("WORDS", &words)], 5, Some("compact_scatter"))?,
+            scan: PrefixSum::new(device)?,
+            _ty: PhantomData,
+        })
+    }
+
+    ///
+    /// Records the compaction of input into output and the number of kept elements into count[0].
+    /// Flags have to have the same length as the input, the output has to be at least as long
+    /// as the input.
+    ///
+    pub fn encode(&self, device: &Device, encoder: &mut wgpu::CommandEncoder, input: &Buffer<T>, flags: &Buffer<u32>, output: &Buffer<T>, count: &Buffer<u32>) -> Result<()>{
+        if flags.len() != input.len(){
+            return Err(anyhow::anyhow!("Compacting {} elements with {} flags", input.len(), flags.len()));
+        }
+        if output.len() < input.len(){
+            return Err(anyhow::anyhow!("Output of {} elements can not hold {} elements", output.len(), input.len()));
+        }
+        let n = super::item_count(input)?;
+
+        let keep = super::scratch::<u32>(device, input.len());
+        let offsets = super::scratch::<u32>(device, input.len());
+
+        self.flags.encode(device, encoder, &[flags, &keep], n, 0)?;
+        self.scan.encode_exclusive(device, encoder, &keep, &offsets)?;
+        self.scatter.encode(device, encoder, &[input, &keep, &offsets, output, count], n, 0)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+//!
+//! Parallel primitives implemented as compute shaders.
+//!
+//! Every algorithm is compiled once when it is created and records its passes into a caller
+//! provided encoder. Temporary buffers are created while encoding and released by wgpu once the
+//! commands have been executed.
+//! All algorithms use push constants and therefore require Features::PUSH_CONSTANTS.
+//!
+
+use std::sync::Arc;
+use anyhow::Result;
+use crate::*;
+
+pub mod reduce;
+pub mod scan;
+pub mod sort;
+pub mod compact;
+
+pub use reduce::*;
+pub use scan::*;
+pub use sort::*;
+pub use compact::*;
+
+///
+/// The local size of all bundled shaders.
+///
+pub const WORKGROUP_SIZE: u32 = 256;
+
+///
+/// An element type that the arithmetic algorithms can operate on.
+///
+/// The constants are GLSL expressions used to specialize the bundled shaders.
+///
+pub trait Element: bytemuck::Pod{
+    const GLSL_TYPE: &'static str;
+    const GLSL_ZERO: &'static str;
+    /// The largest value of the type, the identity of min.
+    const GLSL_MAX: &'static str;
+    /// The smallest value of the type, the identity of max.
+    const GLSL_MIN: &'static str;
+}
+
+impl Element for u32{
+    const GLSL_TYPE: &'static str = "uint";
+    const GLSL_ZERO: &'static str = "0u";
+    const GLSL_MAX: &'static str = "0xFFFFFFFFu";
+    const GLSL_MIN: &'static str = "0u";
+}
+
+impl Element for i32{
+    const GLSL_TYPE: &'static str = "int";
+    const GLSL_ZERO: &'static str = "0";
+    const GLSL_MAX: &'static str = "0x7FFFFFFF";
+    const GLSL_MIN: &'static str = "(-0x7FFFFFFF - 1)";
+}
+
+impl Element for f32{
+    const GLSL_TYPE: &'static str = "float";
+    const GLSL_ZERO: &'static str = "0.0";
+    const GLSL_MAX: &'static str = "uintBitsToFloat(0x7F800000u)";
+    const GLSL_MIN: &'static str = "uintBitsToFloat(0xFF800000u)";
+}
+
+///
+/// A compute pipeline of one of the bundled shaders.
+///
+/// All bindings are read-write storage buffers in set 0 and the push constants hold the number
+/// of items and one parameter.
+///
+struct StorageKernel{
+    pipeline: ComputePipeline,
+    layout: Arc<wgpu::BindGroupLayout>,
+}
+
+impl StorageKernel{
+    fn new(device: &Device, src: &str, defines: &[(&str, &str)], bindings: usize, label: Option<&str>) -> Result<Self>{
+        let shader = ComputeShader::from_src_with_defines(device, src, defines, label)?;
+
+        let entries: Vec<BindGroupLayoutEntry> = (0..bindings)
+            .map(|_| BindGroupLayoutEntry::new(wgpu::ShaderStages::COMPUTE, wgpu::BindingType::Buffer{
+                ty: wgpu::BufferBindingType::Storage{read_only: false},
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            }))
+            .collect();
+        let layout = device.bind_group_layout(&binding::layout_entries(&entries), label);
+
+        let pipeline_layout = PipelineLayout::try_new(
+            device,
+            &[&layout],
+            &[<[u32; 2]>::push_const_layout(wgpu::ShaderStages::COMPUTE)],
+            label
+        )?;
+
+        let pipeline = ComputePipelineBuilder::new(&shader)
+            .set_label(label)
+            .set_layout(&pipeline_layout)
+            .build(device);
+
+        Ok(Self{
+            pipeline,
+            layout,
+        })
+    }
+
+    ///
+    /// Records one dispatch covering n items.
+    /// At least one workgroup is dispatched so that kernels always write their results.
+    ///
+    fn encode(&self, device: &Device, encoder: &mut wgpu::CommandEncoder, buffers: &[&wgpu::Buffer], n: u32, param: u32) -> Result<()>{
+        let [x, y, z] = self.pipeline.workgroup_count([n.max(1), 1, 1])?;
+
+        let entries: Vec<wgpu::BindGroupEntry> = buffers.iter()
+            .enumerate()
+            .map(|(i, buffer)| wgpu::BindGroupEntry{
+                binding: i as u32,
+                resource: buffer.as_entire_binding(),
+            })
+            .collect();
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor{
+            label: None,
+            layout: &self.layout,
+            entries: &entries,
+        });
+
+        let mut cpass = ComputePass::new(encoder, None);
+        let mut cpass_ppl = cpass.set_pipeline(&self.pipeline);
+
+        cpass_ppl.set_bind_group(0, &bind_group, &[]);
+        cpass_ppl.set_push_const(0, &[n, param])?;
+        cpass_ppl.dispatch(x, y, z);
+        Ok(())
+    }
+}
+
+///
+/// Creates a storage buffer for intermediate results.
+///
+fn scratch<C: bytemuck::Pod>(device: &Device, len: usize) -> Buffer<C>{
+    Buffer::new_empty(device, wgpu::BufferUsages::STORAGE, Some("algorithms scratch"), len.max(1))
+}
+
+///
+/// Returns an error if the buffer holds more items than a single dispatch can address.
+///
+fn item_count<C: bytemuck::Pod>(buffer: &Buffer<C>) -> Result<u32>{
+    u32::try_from(buffer.len())
+        .map_err(|_| anyhow::anyhow!("Buffer of {} items is too large", buffer.len()))
+}
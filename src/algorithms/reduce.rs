@@ -0,0 +1,79 @@
+use std::marker::PhantomData;
+use anyhow::Result;
+use crate::*;
+use super::{Element, StorageKernel, WORKGROUP_SIZE};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReduceOp{
+    Sum,
+    Min,
+    Max,
+}
+
+///
+/// Reduces a buffer to a single element.
+///
+/// Every pass reduces blocks of WORKGROUP_SIZE elements until one element remains, which is
+/// written to the first element of the output.
+///
+pub struct Reduce<T: Element>{
+    kernel: StorageKernel,
+    _ty: PhantomData<T>,
+}
+
+impl<T: Element> Reduce<T>{
+    pub fn new(device: &Device, op: ReduceOp) -> Result<Self>{
+        let (op_index, identity) = match op{
+            ReduceOp::Sum => ("0", T::GLSL_ZERO),
+            ReduceOp::Min => ("1", T::GLSL_MAX),
+            ReduceOp::Max => ("2", T::GLSL_MIN),
+        };
+        let kernel = StorageKernel::new(device, include_str!("shaders/reduce.glsl"), &[
+            ("ELEMENT", T::GLSL_TYPE),
+            ("IDENTITY", identity),
+            ("REDUCE_OP", op_index),
+        ], 2, Some("reduce"))?;
+
+        Ok(Self{
+            kernel,
+            _ty: PhantomData,
+        })
+    }
+
+    #[inline]
+    pub fn sum(device: &Device) -> Result<Self>{
+        Self::new(device, ReduceOp::Sum)
+    }
+
+    #[inline]
+    pub fn min(device: &Device) -> Result<Self>{
+        Self::new(device, ReduceOp::Min)
+    }
+
+    #[inline]
+    pub fn max(device: &Device) -> Result<Self>{
+        Self::new(device, ReduceOp::Max)
+    }
+
+    ///
+    /// Records the reduction of input into output[0].
+    ///
+    pub fn encode(&self, device: &Device, encoder: &mut wgpu::CommandEncoder, input: &Buffer<T>, output: &Buffer<T>) -> Result<()>{
+        let mut lens = vec![super::item_count(input)?];
+        while lens[lens.len() - 1] > WORKGROUP_SIZE{
+            lens.push(lens[lens.len() - 1].div_ceil(WORKGROUP_SIZE));
+        }
+
+        // One scratch buffer for the result of every pass except the last.
+        let scratch: Vec<Buffer<T>> = lens[1..].iter()
+            .map(|&len| super::scratch(device, len as usize))
+            .collect();
+
+        for (i, &len) in lens.iter().enumerate(){
+            let src = if i == 0 {input} else {&scratch[i - 1]};
+            let dst = if i == scratch.len() {output} else {&scratch[i]};
+            self.kernel.encode(device, encoder, &[src, dst], len, 0)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+use std::marker::PhantomData;
+use anyhow::Result;
+use crate::*;
+use super::{Element, StorageKernel, WORKGROUP_SIZE};
+
+///
+/// Computes inclusive and exclusive prefix sums.
+///
+/// Blocks of WORKGROUP_SIZE elements are scanned independently, the block totals are scanned
+/// recursively and added to the blocks afterwards.
+///
+pub struct PrefixSum<T: Element>{
+    scan_block: StorageKernel,
+    scan_add: StorageKernel,
+    _ty: PhantomData<T>,
+}
+
+impl<T: Element> PrefixSum<T>{
+    pub fn new(device: &Device) -> Result<Self>{
+        let defines = [
+            ("ELEMENT", T::GLSL_TYPE),
+            ("ZERO", T::GLSL_ZERO),
+        ];
+        Ok(Self{
+            scan_block: StorageKernel::new(device, include_str!("shaders/scan_block.glsl"), &defines, 3, Some("scan_block"))?,
+            scan_add: StorageKernel::new(device, include_str!("shaders/scan_add.glsl"), &defines, 2, Some("scan_add"))?,
+            _ty: PhantomData,
+        })
+    }
+
+    ///
+    /// Records output[i] = input[0] + ... + input[i].
+    /// Input and output have to be different buffers of at least the length of the input.
+    ///
+    pub fn encode_inclusive(&self, device: &Device, encoder: &mut wgpu::CommandEncoder, input: &Buffer<T>, output: &Buffer<T>) -> Result<()>{
+        self.encode_scan(device, encoder, input, output, super::item_count(input)?, false)
+    }
+
+    ///
+    /// Records output[i] = input[0] + ... + input[i - 1] with output[0] = 0.
+    /// Input and output have to be different buffers of at least the length of the input.
+    ///
+    pub fn encode_exclusive(&self, device: &Device, encoder: &mut wgpu::CommandEncoder, input: &Buffer<T>, output: &Buffer<T>) -> Result<()>{
+        self.encode_scan(device, encoder, input, output, super::item_count(input)?, true)
+    }
+
+    fn encode_scan(&self, device: &Device, encoder: &mut wgpu::CommandEncoder, input: &wgpu::Buffer, output: &wgpu::Buffer, n: u32, exclusive: bool) -> Result<()>{
+        let blocks = n.div_ceil(WORKGROUP_SIZE);
+        let block_sums = super::scratch::<T>(device, blocks as usize);
+
+        self.scan_block.encode(device, encoder, &[input, output, &block_sums], n, exclusive as u32)?;
+
+        if blocks > 1{
+            let block_offsets = super::scratch::<T>(device, blocks as usize);
+            self.encode_scan(device, encoder, &block_sums, &block_offsets, blocks, true)?;
+            self.scan_add.encode(device, encoder, &[output, &block_offsets], n, 0)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,27 @@
+#version 460
+#if COMPUTE_SHADER
+
+// Normalizes the flags to 0 or 1 so that they can be scanned.
+
+layout(local_size_x = 256) in;
+
+layout(set = 0, binding = 0) buffer Flags{
+    uint flags[];
+};
+layout(set = 0, binding = 1) buffer Keep{
+    uint keep[];
+};
+layout(push_constant) uniform Params{
+    uint n;
+    uint param;
+};
+
+void main(){
+    uint i = gl_GlobalInvocationID.x;
+
+    if (i < n){
+        keep[i] = flags[i] != 0 ? 1 : 0;
+    }
+}
+
+#endif
@@ -0,0 +1,42 @@
+#version 460
+#if COMPUTE_SHADER
+
+// Moves every kept element to its scanned offset and writes the number of kept elements.
+// Elements are copied as WORDS 32 bit words, WORDS is defined by algorithms::Compact.
+
+layout(local_size_x = 256) in;
+
+layout(set = 0, binding = 0) buffer InBuffer{
+    uint in_buf[];
+};
+layout(set = 0, binding = 1) buffer Keep{
+    uint keep[];
+};
+layout(set = 0, binding = 2) buffer Offsets{
+    uint offsets[];
+};
+layout(set = 0, binding = 3) buffer OutBuffer{
+    uint out_buf[];
+};
+layout(set = 0, binding = 4) buffer Count{
+    uint count;
+};
+layout(push_constant) uniform Params{
+    uint n;
+    uint param;
+};
+
+void main(){
+    uint i = gl_GlobalInvocationID.x;
+
+    if (i < n && keep[i] != 0){
+        for (uint w = 0; w < WORDS; w++){
+            out_buf[offsets[i] * WORDS + w] = in_buf[i * WORDS + w];
+        }
+    }
+    if (i == 0){
+        count = n > 0 ? offsets[n - 1] + keep[n - 1] : 0;
+    }
+}
+
+#endif
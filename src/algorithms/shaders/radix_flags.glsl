@@ -0,0 +1,27 @@
+#version 460
+#if COMPUTE_SHADER
+
+// Writes 1 for every key whose bit is not set and 0 otherwise.
+
+layout(local_size_x = 256) in;
+
+layout(set = 0, binding = 0) buffer Keys{
+    uint keys[];
+};
+layout(set = 0, binding = 1) buffer Flags{
+    uint flags[];
+};
+layout(push_constant) uniform Params{
+    uint n;
+    uint bit;
+};
+
+void main(){
+    uint i = gl_GlobalInvocationID.x;
+
+    if (i < n){
+        flags[i] = ((keys[i] >> bit) & 1) == 0 ? 1 : 0;
+    }
+}
+
+#endif
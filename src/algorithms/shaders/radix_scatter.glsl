@@ -0,0 +1,49 @@
+#version 460
+#if COMPUTE_SHADER
+
+// Stable split of the keys by one bit using the exclusive scan of the flags.
+// Keys whose bit is not set are moved to the front.
+// SORT_VALUES is defined by algorithms::RadixSort.
+
+layout(local_size_x = 256) in;
+
+layout(set = 0, binding = 0) buffer KeysIn{
+    uint keys_in[];
+};
+layout(set = 0, binding = 1) buffer KeysOut{
+    uint keys_out[];
+};
+layout(set = 0, binding = 2) buffer Flags{
+    uint flags[];
+};
+layout(set = 0, binding = 3) buffer Offsets{
+    uint offsets[];
+};
+#if SORT_VALUES
+layout(set = 0, binding = 4) buffer ValuesIn{
+    uint values_in[];
+};
+layout(set = 0, binding = 5) buffer ValuesOut{
+    uint values_out[];
+};
+#endif
+layout(push_constant) uniform Params{
+    uint n;
+    uint param;
+};
+
+void main(){
+    uint i = gl_GlobalInvocationID.x;
+
+    if (i < n){
+        uint total_unset = offsets[n - 1] + flags[n - 1];
+        uint dst = flags[i] != 0 ? offsets[i] : i - offsets[i] + total_unset;
+
+        keys_out[dst] = keys_in[i];
+#if SORT_VALUES
+        values_out[dst] = values_in[i];
+#endif
+    }
+}
+
+#endif
@@ -0,0 +1,51 @@
+#version 460
+#if COMPUTE_SHADER
+
+// Reduces every workgroup sized block of the input to one element of the output.
+// ELEMENT, IDENTITY and REDUCE_OP are defined by algorithms::Reduce.
+
+layout(local_size_x = 256) in;
+
+layout(set = 0, binding = 0) buffer InBuffer{
+    ELEMENT in_buf[];
+};
+layout(set = 0, binding = 1) buffer OutBuffer{
+    ELEMENT out_buf[];
+};
+layout(push_constant) uniform Params{
+    uint n;
+    uint param;
+};
+
+shared ELEMENT partial[256];
+
+ELEMENT op(ELEMENT a, ELEMENT b){
+#if REDUCE_OP == 0
+    return a + b;
+#elif REDUCE_OP == 1
+    return min(a, b);
+#else
+    return max(a, b);
+#endif
+}
+
+void main(){
+    uint i = gl_GlobalInvocationID.x;
+    uint lid = gl_LocalInvocationID.x;
+
+    partial[lid] = i < n ? in_buf[i] : IDENTITY;
+    barrier();
+
+    for (uint stride = gl_WorkGroupSize.x / 2; stride > 0; stride >>= 1){
+        if (lid < stride){
+            partial[lid] = op(partial[lid], partial[lid + stride]);
+        }
+        barrier();
+    }
+
+    if (lid == 0){
+        out_buf[gl_WorkGroupID.x] = partial[0];
+    }
+}
+
+#endif
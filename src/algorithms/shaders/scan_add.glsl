@@ -0,0 +1,28 @@
+#version 460
+#if COMPUTE_SHADER
+
+// Adds the scanned block sums to every element of their block.
+// ELEMENT is defined by algorithms::PrefixSum.
+
+layout(local_size_x = 256) in;
+
+layout(set = 0, binding = 0) buffer OutBuffer{
+    ELEMENT out_buf[];
+};
+layout(set = 0, binding = 1) buffer BlockOffsets{
+    ELEMENT block_offsets[];
+};
+layout(push_constant) uniform Params{
+    uint n;
+    uint param;
+};
+
+void main(){
+    uint i = gl_GlobalInvocationID.x;
+
+    if (i < n){
+        out_buf[i] += block_offsets[gl_WorkGroupID.x];
+    }
+}
+
+#endif
@@ -0,0 +1,53 @@
+#version 460
+#if COMPUTE_SHADER
+
+// Scans every workgroup sized block of the input and writes the total of each block to
+// block_sums. The scan is exclusive if param is not 0.
+// ELEMENT and ZERO are defined by algorithms::PrefixSum.
+
+layout(local_size_x = 256) in;
+
+layout(set = 0, binding = 0) buffer InBuffer{
+    ELEMENT in_buf[];
+};
+layout(set = 0, binding = 1) buffer OutBuffer{
+    ELEMENT out_buf[];
+};
+layout(set = 0, binding = 2) buffer BlockSums{
+    ELEMENT block_sums[];
+};
+layout(push_constant) uniform Params{
+    uint n;
+    uint exclusive;
+};
+
+shared ELEMENT partial[256];
+
+void main(){
+    uint i = gl_GlobalInvocationID.x;
+    uint lid = gl_LocalInvocationID.x;
+
+    partial[lid] = i < n ? in_buf[i] : ZERO;
+    barrier();
+
+    for (uint offset = 1; offset < gl_WorkGroupSize.x; offset <<= 1){
+        ELEMENT value = lid >= offset ? partial[lid - offset] : ZERO;
+        barrier();
+        partial[lid] += value;
+        barrier();
+    }
+
+    if (i < n){
+        if (exclusive != 0){
+            out_buf[i] = lid > 0 ? partial[lid - 1] : ZERO;
+        }
+        else{
+            out_buf[i] = partial[lid];
+        }
+    }
+    if (lid == gl_WorkGroupSize.x - 1){
+        block_sums[gl_WorkGroupID.x] = partial[lid];
+    }
+}
+
+#endif
@@ -0,0 +1,83 @@
+use anyhow::Result;
+use crate::*;
+use super::{PrefixSum, StorageKernel};
+
+///
+/// Sorts u32 keys or key-value pairs in ascending order of the keys.
+///
+/// Uses a least significant bit first radix sort with one stable split per bit, so the order of
+/// equal keys is preserved.
+///
+pub struct RadixSort{
+    flags: StorageKernel,
+    scatter_keys: StorageKernel,
+    scatter_pairs: StorageKernel,
+    scan: PrefixSum<u32>,
+}
+
+impl RadixSort{
+    pub fn new(device: &Device) -> Result<Self>{
+        let scatter_src = include_str!("shaders/radix_scatter.glsl");
+        Ok(Self{
+            flags: StorageKernel::new(device, include_str!("shaders/radix_flags.glsl"), &[], 2, Some("radix_flags"))?,
+            scatter_keys: StorageKernel::new(device, scatter_src, &[("SORT_VALUES", "0")], 4, Some("radix_scatter_keys"))?,
+            scatter_pairs: StorageKernel::new(device, scatter_src, &[("SORT_VALUES", "1")], 6, Some("radix_scatter_pairs"))?,
+            scan: PrefixSum::new(device)?,
+        })
+    }
+
+    ///
+    /// Records sorting the keys in place.
+    ///
+    pub fn encode_keys(&self, device: &Device, encoder: &mut wgpu::CommandEncoder, keys: &Buffer<u32>) -> Result<()>{
+        self.encode_sort(device, encoder, keys, None)
+    }
+
+    ///
+    /// Records sorting the keys in place and moving every value along with its key.
+    /// Keys and values have to have the same length.
+    ///
+    pub fn encode_pairs(&self, device: &Device, encoder: &mut wgpu::CommandEncoder, keys: &Buffer<u32>, values: &Buffer<u32>) -> Result<()>{
+        if keys.len() != values.len(){
+            return Err(anyhow::anyhow!("Sorting {} keys with {} values", keys.len(), values.len()));
+        }
+        self.encode_sort(device, encoder, keys, Some(values))
+    }
+
+    fn encode_sort(&self, device: &Device, encoder: &mut wgpu::CommandEncoder, keys: &Buffer<u32>, values: Option<&Buffer<u32>>) -> Result<()>{
+        let n = super::item_count(keys)?;
+        let len = keys.len();
+
+        let flags = super::scratch::<u32>(device, len);
+        let offsets = super::scratch::<u32>(device, len);
+        let keys_tmp = super::scratch::<u32>(device, len);
+        let values_tmp = values.map(|_| super::scratch::<u32>(device, len));
+
+        // An even number of passes leaves the sorted keys in the original buffers.
+        for bit in 0..u32::BITS{
+            let (keys_in, keys_out): (&wgpu::Buffer, &wgpu::Buffer) = if bit % 2 == 0{
+                (keys, &keys_tmp)
+            } else{
+                (&keys_tmp, keys)
+            };
+
+            self.flags.encode(device, encoder, &[keys_in, &flags], n, bit)?;
+            self.scan.encode_exclusive(device, encoder, &flags, &offsets)?;
+
+            match (values, &values_tmp){
+                (Some(values), Some(values_tmp)) => {
+                    let (values_in, values_out): (&wgpu::Buffer, &wgpu::Buffer) = if bit % 2 == 0{
+                        (values, values_tmp)
+                    } else{
+                        (values_tmp, values)
+                    };
+                    self.scatter_pairs.encode(device, encoder, &[keys_in, keys_out, &flags, &offsets, values_in, values_out], n, 0)?;
+                }
+                _ => {
+                    self.scatter_keys.encode(device, encoder, &[keys_in, keys_out, &flags, &offsets], n, 0)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
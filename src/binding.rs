@@ -1,10 +1,14 @@
 #[allow(unused)]
 use anyhow::*;
 use std::marker::PhantomData;
+use std::cell::{Cell, OnceCell};
+use std::sync::Arc;
+use crate::Device;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 pub trait CreateBindGroupLayout {
     fn create_bind_group_layout(
-        device: &wgpu::Device,
+        device: &Device,
         label: Option<&str>,
     ) -> BindGroupLayoutWithDesc;
 }
@@ -26,8 +30,23 @@ impl GetBindGroup for wgpu::BindGroup {
     }
 }
 
+///
+/// A unique id of a gpu resource such as a Buffer or Texture.
+/// A new id is generated every time the resource is (re)allocated, which lets a Bound detect
+/// that its BindGroup references a stale resource.
+///
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct ResourceId(u64);
+
+impl ResourceId{
+    pub fn unique() -> Self{
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        Self(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
 pub struct BindGroupLayoutWithDesc {
-    pub layout: wgpu::BindGroupLayout,
+    pub layout: Arc<wgpu::BindGroupLayout>,
     pub entries: Vec<wgpu::BindGroupLayoutEntry>,
 }
 
@@ -35,6 +54,8 @@ pub struct BindGroupLayoutEntry {
     pub visibility: wgpu::ShaderStages,
     pub ty: wgpu::BindingType,
     pub count: Option<std::num::NonZeroU32>,
+    /// An explicit binding index. Entries without one use the index following the previous entry.
+    pub binding: Option<u32>,
 }
 
 impl BindGroupLayoutEntry {
@@ -43,38 +64,180 @@ impl BindGroupLayoutEntry {
             visibility,
             ty,
             count: None,
+            binding: None,
+        }
+    }
+
+    pub fn with_binding(mut self, binding: u32) -> Self {
+        self.binding = Some(binding);
+        self
+    }
+
+    pub fn with_visibility(mut self, visibility: wgpu::ShaderStages) -> Self {
+        self.visibility = visibility;
+        self
+    }
+
+    ///
+    /// Makes storage buffers and storage textures read only.
+    /// Has no effect on other bindings.
+    ///
+    pub fn read_only(mut self) -> Self {
+        match &mut self.ty {
+            wgpu::BindingType::Buffer{ty: wgpu::BufferBindingType::Storage{read_only}, ..} => {
+                *read_only = true;
+            },
+            wgpu::BindingType::StorageTexture{access, ..} => {
+                *access = wgpu::StorageTextureAccess::ReadOnly;
+            },
+            _ => {},
+        }
+        self
+    }
+
+    ///
+    /// Binds a buffer as uniform buffer instead of storage buffer.
+    /// Has no effect on other bindings.
+    ///
+    pub fn uniform(mut self) -> Self {
+        if let wgpu::BindingType::Buffer{ty, ..} = &mut self.ty {
+            *ty = wgpu::BufferBindingType::Uniform;
         }
+        self
     }
+
+    ///
+    /// Requires a dynamic offset when setting the BindGroup.
+    /// Has no effect on non buffer bindings.
+    ///
+    pub fn dynamic_offset(mut self) -> Self {
+        if let wgpu::BindingType::Buffer{has_dynamic_offset, ..} = &mut self.ty {
+            *has_dynamic_offset = true;
+        }
+        self
+    }
+
+    ///
+    /// Sets the minimum size in bytes of a buffer binding.
+    /// Has no effect on non buffer bindings.
+    ///
+    pub fn min_binding_size(mut self, size: u64) -> Self {
+        if let wgpu::BindingType::Buffer{min_binding_size, ..} = &mut self.ty {
+            *min_binding_size = std::num::NonZeroU64::new(size);
+        }
+        self
+    }
+}
+
+///
+/// A resource returned by BindGroupContent::resources.
+///
+/// Binding arrays own the list of views or buffer bindings they reference. It is built from the
+/// content when the BindGroup is created so no content has to reference itself.
+///
+pub enum BindingResource<'a>{
+    Single(wgpu::BindingResource<'a>),
+    TextureViewArray(Vec<&'a wgpu::TextureView>),
+    BufferArray(Vec<wgpu::BufferBinding<'a>>),
+}
+
+impl<'a> From<wgpu::BindingResource<'a>> for BindingResource<'a>{
+    fn from(resource: wgpu::BindingResource<'a>) -> Self{
+        Self::Single(resource)
+    }
+}
+
+impl<'a> BindingResource<'a>{
+    pub fn as_wgpu(&self) -> wgpu::BindingResource<'_>{
+        match self{
+            Self::Single(resource) => resource.clone(),
+            Self::TextureViewArray(views) => wgpu::BindingResource::TextureViewArray(views),
+            Self::BufferArray(bindings) => wgpu::BindingResource::BufferArray(bindings),
+        }
+    }
+}
+
+///
+/// Limits a buffer binding to size bytes starting at its offset so that it can be bound with a
+/// dynamic offset without exceeding the buffer.
+/// Other resources are returned unchanged.
+///
+pub fn dynamic_binding_resource(resource: BindingResource, size: u64) -> BindingResource{
+    match resource{
+        BindingResource::Single(wgpu::BindingResource::Buffer(binding)) => wgpu::BindingResource::Buffer(wgpu::BufferBinding{
+            size: std::num::NonZeroU64::new(size),
+            ..binding
+        }).into(),
+        resource => resource,
+    }
+}
+
+///
+/// A trait implemented for buffer bindings that know the size of one of their elements.
+/// Used by the min_binding_size attribute of the BindGroupContent derive macro.
+///
+pub trait BindingSize {
+    fn binding_size() -> u64;
 }
 
 ///
 /// A trait implemented for structs that can be the content of a BindGroup.
 ///
+/// It can be derived for structs whose fields implement BindGroupContent.
+/// Binding indices are assigned at compile time so that a field whose implicit index collides
+/// with an explicit one fails to compile:
+///
+/// ```compile_fail
+/// use ewgpu::*;
+///
+/// #[derive(BindGroupContent)]
+/// struct Clash{
+///     // Occupies the bindings 0 and 1.
+///     texture: SampledTexture,
+///     #[binding = 1]
+///     buffer: Buffer<u32>,
+/// }
+/// ```
+///
 pub trait BindGroupContent: Sized {
+    ///
+    /// The number of consecutive binding indices occupied by the entries.
+    /// Used by the BindGroupContent derive macro to assign binding indices at compile time.
+    ///
+    const BINDING_COUNT: u32;
     ///
     /// Returns a vector of entries for this BindGroupContent. Used to create layout.
     /// The visibility option is a visibility override if all elements in a struct should have some
     /// visibility.
     ///
     fn entries(visibility: Option<wgpu::ShaderStages>) -> Vec<BindGroupLayoutEntry>;
-    fn resources(&self) -> Vec<wgpu::BindingResource>;
-    fn into_bound(self, device: &wgpu::Device) -> Bound<Self> {
+    fn resources(&self) -> Vec<BindingResource<'_>>;
+    ///
+    /// Returns the ids of the resources referenced by this BindGroupContent.
+    /// They are used by Bound to recreate its BindGroup when a resource has been reallocated.
+    ///
+    fn resource_ids(&self) -> Vec<ResourceId>{
+        Vec::new()
+    }
+    fn into_bound(self, device: &Arc<Device>) -> Bound<Self> {
         Bound{
-            bind_group: Self::create_bind_group(&self, device),
+            bind_group: OnceCell::from(Self::create_bind_group(&self, device)),
+            stale: Cell::new(None),
+            device: device.clone(),
             content: self,
         }
     }
-    fn create_bind_group(&self, device: &wgpu::Device) -> BindGroup<Self>{
+    fn create_bind_group(&self, device: &Device) -> BindGroup<Self>{
         let layout =
             Self::create_bind_group_layout(device, None);
         let resources = self.resources();
 
         let entries: Vec<wgpu::BindGroupEntry> = resources
-            .into_iter()
+            .iter()
             .enumerate()
             .map(|(i, r)| wgpu::BindGroupEntry {
                 binding: layout.entries[i].binding,
-                resource: r,
+                resource: r.as_wgpu(),
             })
             .collect();
 
@@ -86,50 +249,119 @@ pub trait BindGroupContent: Sized {
         BindGroup{
             bind_group_layout: layout,
             bind_group,
+            resource_ids: self.resource_ids(),
             _ty: PhantomData,
         }
     }
     fn create_bind_group_layout(
-        device: &wgpu::Device,
+        device: &Device,
         label: wgpu::Label,
     ) -> BindGroupLayoutWithDesc {
-        let entries: Vec<wgpu::BindGroupLayoutEntry> = Self::entries(None)
-            .iter()
-            .enumerate()
-            .map(|(i, x)| wgpu::BindGroupLayoutEntry {
-                binding: i as u32,
-                ty: x.ty,
-                count: x.count,
-                visibility: x.visibility,
-            })
-            .collect();
+        let entries = layout_entries(&Self::entries(None));
 
         BindGroupLayoutWithDesc {
-            layout: device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                entries: &entries,
-                label,
-            }),
+            layout: device.bind_group_layout(&entries, label),
             entries,
         }
     }
 }
 
-// TODO: Derive macro for BindGroupContent.
+///
+/// Assigns the first binding index of every field of a struct deriving BindGroupContent.
+/// Fields are given as their explicit binding index and their BINDING_COUNT.
+/// Fields without an explicit binding index use the index following the previous field.
+/// Returns the first binding indices and the number of binding indices spanned by all fields.
+///
+/// Panics, and therefore fails to compile when evaluated in a constant, if a binding index is
+/// used by more than one field.
+///
+#[doc(hidden)]
+pub const fn field_bindings<const N: usize>(fields: [(Option<u32>, u32); N]) -> ([u32; N], u32){
+    let mut starts = [0; N];
+    let mut next = 0;
+    let mut count = 0;
+    let mut i = 0;
+    while i < N{
+        let start = match fields[i].0{
+            Some(binding) => binding,
+            None => next,
+        };
+        next = start + fields[i].1;
+        if next > count{
+            count = next;
+        }
+
+        let mut j = 0;
+        while j < i{
+            let other_end = starts[j] + fields[j].1;
+            if fields[i].1 > 0 && fields[j].1 > 0 && start < other_end && starts[j] < next{
+                panic!("A binding index is used by more than one field");
+            }
+            j += 1;
+        }
+
+        starts[i] = start;
+        i += 1;
+    }
+    (starts, count)
+}
+
+///
+/// Assigns binding indices to the entries.
+/// Entries without an explicit binding index use the index following the previous entry.
+/// Panics if a binding index is used by more than one entry.
+///
+pub fn layout_entries(entries: &[BindGroupLayoutEntry]) -> Vec<wgpu::BindGroupLayoutEntry> {
+    let mut next = 0;
+    let mut used = std::collections::HashSet::new();
+    entries.iter()
+        .map(|x| {
+            let binding = x.binding.unwrap_or(next);
+            if !used.insert(binding) {
+                panic!("Binding index {} is used by more than one entry", binding);
+            }
+            next = binding + 1;
+            wgpu::BindGroupLayoutEntry {
+                binding,
+                ty: x.ty,
+                count: x.count,
+                visibility: x.visibility,
+            }
+        })
+        .collect()
+}
+
+///
+/// Places the entries of one element of a tuple or array at consecutive indices starting at
+/// offset. Entries with an explicit binding index keep their offset relative to the element.
+///
+fn offset_entries(entries: Vec<BindGroupLayoutEntry>, offset: u32) -> impl Iterator<Item = BindGroupLayoutEntry>{
+    entries.into_iter().enumerate()
+        .map(move |(i, entry)|{
+            let binding = offset + entry.binding.unwrap_or(i as u32);
+            entry.with_binding(binding)
+        })
+}
 
 macro_rules! bind_group_content_for_tuple{
     ($($name:ident)+) => {
         #[allow(non_snake_case)]
         impl<$($name: BindGroupContent),+> BindGroupContent for ($($name, )+){
+            const BINDING_COUNT: u32 = 0 $(+ $name::BINDING_COUNT)+;
+            // The offset after the last element is never read.
+            #[allow(unused_assignments)]
             fn entries(visibility: Option<wgpu::ShaderStages>) -> Vec<BindGroupLayoutEntry>{
                 let mut ret = Vec::new();
+                let mut offset = 0;
                 {
                     $(
-                        ret.append(&mut $name::entries(visibility));
+                        ret.extend(offset_entries($name::entries(visibility), offset));
+                        offset += $name::BINDING_COUNT;
                     )+
                 }
                 ret
             }
-            fn resources<'br>(&'br self) -> Vec<wgpu::BindingResource<'br>>{
+            fn resources<'br>(&'br self) -> Vec<BindingResource<'br>>{
                 let ($($name, )+) = self;
                 let mut ret = Vec::new();
                 {
@@ -139,6 +371,16 @@ macro_rules! bind_group_content_for_tuple{
                 }
                 ret
             }
+            fn resource_ids(&self) -> Vec<ResourceId>{
+                let ($($name, )+) = self;
+                let mut ret = Vec::new();
+                {
+                    $(
+                        ret.append(&mut $name.resource_ids());
+                    )+
+                }
+                ret
+            }
         }
     }
 }
@@ -158,44 +400,109 @@ bind_group_content_for_tuple! { A B C D E F G H I J K }
 bind_group_content_for_tuple! { A B C D E F G H I J K L }
 
 impl<C: BindGroupContent, const N: usize> BindGroupContent for [C; N] {
+    const BINDING_COUNT: u32 = N as u32 * C::BINDING_COUNT;
+
     fn entries(visibility: Option<wgpu::ShaderStages>) -> Vec<BindGroupLayoutEntry> {
         let mut ret = Vec::with_capacity(N);
-        for _i in 0..N {
-            ret.append(&mut C::entries(visibility));
+        for i in 0..N {
+            ret.extend(offset_entries(C::entries(visibility), i as u32 * C::BINDING_COUNT));
         }
         ret
     }
 
-    fn resources(&self) -> Vec<wgpu::BindingResource> {
+    fn resources(&self) -> Vec<BindingResource<'_>> {
         let mut ret = Vec::with_capacity(N);
         for content in self {
             ret.append(&mut content.resources());
         }
         ret
     }
+
+    fn resource_ids(&self) -> Vec<ResourceId> {
+        let mut ret = Vec::with_capacity(N);
+        for content in self {
+            ret.append(&mut content.resource_ids());
+        }
+        ret
+    }
 }
 
-#[derive(DerefMut)]
+///
+/// BindGroupContent together with its BindGroup.
+///
+/// The Bound keeps a handle to the device so that it can recreate its BindGroup the next time it
+/// is requested after a resource in the content has been reallocated, for example by
+/// Buffer::resize.
+///
+/// ```ignore
+/// let mut buffer = BufferBuilder::<u32>::new()
+///     .storage()
+///     .build_empty(&gpu.device, 1)
+///     .into_bound(&gpu.device);
+///
+/// buffer.resize_clear(4, &gpu.device);
+/// // The BindGroup is recreated with the new buffer here.
+/// cpass_ppl.set_bind_group(0, &buffer, &[]);
+/// ```
+///
 pub struct Bound<C: BindGroupContent>{
-    #[target]
-    pub content: C,
-    bind_group: BindGroup<C>,
+    content: C,
+    device: Arc<Device>,
+    // Empty after the content has been borrowed mutably until the BindGroup is requested again.
+    bind_group: OnceCell<BindGroup<C>>,
+    // The BindGroup from before the content was borrowed mutably. It is reused if the resources
+    // of the content have not been reallocated.
+    stale: Cell<Option<BindGroup<C>>>,
 }
 
 impl<C: BindGroupContent> Bound<C> {
     ///
-    /// Has to be called whenever a buffer is changed for example when expand_to_clear is called on
-    /// it.
-    /// TODO: Change this requirement.
+    /// Recreates the BindGroup if a resource of the content has been reallocated.
+    /// This is not required before using the Bound since the BindGroup is recreated when it is
+    /// requested, but allows doing so outside of pass encoding.
     ///
-    pub fn update(&mut self, device: &wgpu::Device) {
-        self.bind_group.update(&self.content, device);
+    pub fn update(&mut self) {
+        self.current();
+    }
+
+    ///
+    /// Returns the BindGroup, recreating it if a resource of the content has been reallocated
+    /// since it was created. Content without resource ids is always recreated after it has been
+    /// borrowed mutably.
+    ///
+    fn current(&self) -> &BindGroup<C>{
+        self.bind_group.get_or_init(|| {
+            let resource_ids = self.content.resource_ids();
+            match self.stale.take(){
+                Some(bind_group) if !resource_ids.is_empty() && bind_group.resource_ids == resource_ids => bind_group,
+                _ => self.content.create_bind_group(&self.device),
+            }
+        })
+    }
+}
+
+impl<C: BindGroupContent> std::ops::Deref for Bound<C>{
+    type Target = C;
+
+    fn deref(&self) -> &Self::Target {
+        &self.content
+    }
+}
+
+impl<C: BindGroupContent> std::ops::DerefMut for Bound<C>{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // The content might reallocate a resource so the BindGroup is checked when it is next
+        // requested.
+        if let Some(bind_group) = self.bind_group.take(){
+            self.stale.set(Some(bind_group));
+        }
+        &mut self.content
     }
 }
 
 impl<C: BindGroupContent> CreateBindGroupLayout for Bound<C> {
     fn create_bind_group_layout(
-        device: &wgpu::Device,
+        device: &Device,
         label: Option<&str>,
     ) -> BindGroupLayoutWithDesc {
         C::create_bind_group_layout(device, label)
@@ -204,13 +511,13 @@ impl<C: BindGroupContent> CreateBindGroupLayout for Bound<C> {
 
 impl<C: BindGroupContent> GetBindGroupLayout for Bound<C> {
     fn bind_group_layout(&self) -> &BindGroupLayoutWithDesc {
-        &self.bind_group.bind_group_layout()
+        self.current().bind_group_layout()
     }
 }
 
 impl<C: BindGroupContent> GetBindGroup for Bound<C> {
     fn bind_group(&self) -> &wgpu::BindGroup {
-        &self.bind_group
+        &self.current().bind_group
     }
 }
 
@@ -228,23 +535,144 @@ impl From<Bound<super::Texture>> for imgui_wgpu::Texture {
 }
 */
 
+///
+/// The index of a bind group in a pipeline layout with the BindGroupContent C.
+/// Slots are generated by pipeline_layout! for named bind groups.
+///
+/// ```ignore
+/// let layout = pipeline_layout!(&gpu.device,
+///     bind_groups: {
+///         camera: Uniform<Camera>,
+///     },
+///     push_constants: {}
+/// );
+///
+/// rpass_ppl.set_bind_group_slot(layout.slots.camera, &camera, &[]);
+/// ```
+///
+#[derive(Debug)]
+pub struct BindGroupSlot<C: BindGroupContent>{
+    pub index: u32,
+    _ty: PhantomData<C>,
+}
+
+impl<C: BindGroupContent> Clone for BindGroupSlot<C>{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C: BindGroupContent> Copy for BindGroupSlot<C>{}
+
+impl<C: BindGroupContent> BindGroupSlot<C>{
+    pub fn new(index: u32) -> Self{
+        Self{
+            index,
+            _ty: PhantomData,
+        }
+    }
+}
+
+///
+/// A trait mapping the types that can be used for bind groups in pipeline_layout! to their
+/// BindGroupContent.
+///
+pub trait SlotContent{
+    type Content: BindGroupContent;
+}
+
+impl<C: BindGroupContent> SlotContent for C{
+    type Content = C;
+}
+
+impl<C: BindGroupContent> SlotContent for Bound<C>{
+    type Content = C;
+}
+
+impl<C: BindGroupContent> SlotContent for BindGroup<C>{
+    type Content = C;
+}
+
+///
+/// A wrapper that binds the storage buffers and storage textures of its content read only.
+///
+/// ```ignore
+/// #[derive(BindGroupContent)]
+/// struct Particles{
+///     #[visibility(FRAGMENT)]
+///     positions: ReadOnly<Buffer<[f32; 4]>>,
+/// }
+/// ```
+///
+#[derive(DerefMut)]
+pub struct ReadOnly<C: BindGroupContent>{
+    #[target]
+    pub content: C,
+}
+
+impl<C: BindGroupContent> ReadOnly<C>{
+    pub fn new(content: C) -> Self{
+        Self{
+            content,
+        }
+    }
+}
+
+impl<C: BindGroupContent> From<C> for ReadOnly<C>{
+    fn from(content: C) -> Self {
+        Self::new(content)
+    }
+}
+
+impl<C: BindGroupContent> BindGroupContent for ReadOnly<C>{
+    const BINDING_COUNT: u32 = C::BINDING_COUNT;
+
+    fn entries(visibility: Option<wgpu::ShaderStages>) -> Vec<BindGroupLayoutEntry> {
+        C::entries(visibility).into_iter()
+            .map(|entry| entry.read_only())
+            .collect()
+    }
+
+    fn resources(&self) -> Vec<BindingResource<'_>> {
+        self.content.resources()
+    }
+
+    fn resource_ids(&self) -> Vec<ResourceId> {
+        self.content.resource_ids()
+    }
+}
+
+impl<C: BindGroupContent + BindingSize> BindingSize for ReadOnly<C>{
+    fn binding_size() -> u64 {
+        C::binding_size()
+    }
+}
+
 #[derive(DerefMut)]
 pub struct BindGroup<C: BindGroupContent>{
     _ty: PhantomData<C>,
     #[target]
     bind_group: wgpu::BindGroup,
     bind_group_layout: BindGroupLayoutWithDesc,
+    resource_ids: Vec<ResourceId>,
 }
 
 impl<C: BindGroupContent> BindGroup<C>{
-    pub fn update(&mut self, conent: &C, device: &wgpu::Device) {
+    pub fn update(&mut self, conent: &C, device: &Device) {
         *self = conent.create_bind_group(device)
     }
+
+    ///
+    /// Returns the ids of the resources the bind group was created with.
+    ///
+    pub fn resource_ids(&self) -> &[ResourceId]{
+        &self.resource_ids
+    }
 }
 
 impl<C: BindGroupContent> CreateBindGroupLayout for BindGroup<C> {
     fn create_bind_group_layout(
-        device: &wgpu::Device,
+        device: &Device,
         label: Option<&str>,
     ) -> BindGroupLayoutWithDesc {
         C::create_bind_group_layout(device, label)
@@ -427,3 +855,103 @@ pub mod wgsl {
         }
     }
 }
+
+#[cfg(test)]
+mod test{
+    use crate::*;
+
+    #[allow(unused)]
+    #[derive(BindGroupContent)]
+    struct Content{
+        #[visibility(FRAGMENT)]
+        positions: ReadOnly<Buffer<[f32; 4]>>,
+        #[visibility(VERTEX | COMPUTE)]
+        #[uniform]
+        #[dynamic_offset]
+        #[min_binding_size]
+        camera: Buffer<[f32; 16]>,
+        #[min_binding_size = 32]
+        data: Buffer<u32>,
+        other: Buffer<u32>,
+    }
+
+    #[test]
+    fn derive_entries(){
+        let entries = Content::entries(Some(wgpu::ShaderStages::COMPUTE));
+
+        assert_eq!(entries[0].visibility, wgpu::ShaderStages::FRAGMENT);
+        assert_eq!(entries[0].ty, wgpu::BindingType::Buffer{
+            ty: wgpu::BufferBindingType::Storage{read_only: true},
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        });
+
+        assert_eq!(entries[1].visibility, wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::COMPUTE);
+        assert_eq!(entries[1].ty, wgpu::BindingType::Buffer{
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: true,
+            min_binding_size: std::num::NonZeroU64::new(64),
+        });
+
+        assert_eq!(entries[2].ty, wgpu::BindingType::Buffer{
+            ty: wgpu::BufferBindingType::Storage{read_only: false},
+            has_dynamic_offset: false,
+            min_binding_size: std::num::NonZeroU64::new(32),
+        });
+
+        // The visibility override is passed down to fields without a visibility attribute.
+        assert_eq!(entries[3].visibility, wgpu::ShaderStages::COMPUTE);
+    }
+
+    #[allow(unused)]
+    #[derive(BindGroupContent)]
+    struct Sparse{
+        first: Buffer<u32>,
+        #[binding = 3]
+        texture: SampledTexture,
+        after: Buffer<u32>,
+        #[binding = 1]
+        second: Buffer<u32>,
+    }
+
+    #[test]
+    fn sparse_bindings(){
+        let bindings: Vec<u32> = layout_entries(&Sparse::entries(None)).iter()
+            .map(|entry| entry.binding)
+            .collect();
+        assert_eq!(bindings, [0, 3, 4, 5, 1]);
+    }
+
+    #[test]
+    fn sparse_bindings_in_tuple_and_array(){
+        let bindings: Vec<u32> = layout_entries(&<(Buffer<u32>, Sparse, Buffer<u32>)>::entries(None)).iter()
+            .map(|entry| entry.binding)
+            .collect();
+        assert_eq!(bindings, [0, 1, 4, 5, 6, 2, 7]);
+
+        let bindings: Vec<u32> = layout_entries(&<[Sparse; 2]>::entries(None)).iter()
+            .map(|entry| entry.binding)
+            .collect();
+        assert_eq!(bindings, [0, 3, 4, 5, 1, 6, 9, 10, 11, 7]);
+    }
+
+    #[test]
+    fn field_bindings_assigned(){
+        assert_eq!(field_bindings([(None, 1), (Some(3), 2), (None, 1), (Some(1), 1)]), ([0, 3, 5, 1], 6));
+        assert_eq!(Sparse::BINDING_COUNT, 6);
+    }
+
+    #[test]
+    #[should_panic]
+    fn field_bindings_clash(){
+        field_bindings([(None, 2), (Some(1), 1)]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn duplicate_bindings(){
+        layout_entries(&<(Buffer<u32>, Buffer<u32>)>::entries(None).into_iter()
+            .map(|entry| entry.with_binding(0))
+            .collect::<Vec<_>>());
+    }
+}
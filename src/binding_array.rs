@@ -0,0 +1,130 @@
+use crate::*;
+use anyhow::{Result, ensure};
+use std::num::NonZeroU32;
+
+///
+/// N views of textures bound as a single arrayed binding with count N.
+///
+/// Requires the TEXTURE_BINDING_ARRAY feature which can be enabled with
+/// GPUContextBuilder::enable_binding_arrays.
+/// Indexing the array with a non uniform index in a shader additionally requires
+/// GPUContextBuilder::enable_non_uniform_indexing.
+///
+/// ```ignore
+/// let materials = TextureArray::new(&gpu.device, [albedo_view, normal_view])?
+///     .into_bound(&gpu.device);
+/// ```
+///
+pub struct TextureArray<const N: usize, K: kind::SampleKind = kind::Float, D: dim::ViewDimension = dim::D2>{
+    views: [TextureView<K, D>; N],
+}
+
+impl<const N: usize, K: kind::SampleKind, D: dim::ViewDimension> TextureArray<N, K, D>{
+    ///
+    /// Creates a TextureArray.
+    /// Returns an error if the TEXTURE_BINDING_ARRAY feature is not enabled on the device.
+    ///
+    pub fn new(device: &wgpu::Device, views: [TextureView<K, D>; N]) -> Result<Self>{
+        ensure!(device.features().contains(wgpu::Features::TEXTURE_BINDING_ARRAY),
+            "TextureArray requires the TEXTURE_BINDING_ARRAY feature");
+        ensure!(N > 0, "TextureArray requires at least one view");
+
+        Ok(Self{
+            views,
+        })
+    }
+
+    #[inline]
+    pub fn views(&self) -> &[TextureView<K, D>]{
+        &self.views
+    }
+}
+
+impl<const N: usize, K: kind::SampleKind, D: dim::ViewDimension> BindGroupContent for TextureArray<N, K, D>{
+    const BINDING_COUNT: u32 = 1;
+
+    fn entries(visibility: Option<wgpu::ShaderStages>) -> Vec<BindGroupLayoutEntry> {
+        TextureView::<K, D>::entries(visibility).into_iter()
+            .map(|entry| BindGroupLayoutEntry{
+                count: NonZeroU32::new(N as u32),
+                ..entry
+            })
+            .collect()
+    }
+
+    fn resources(&self) -> Vec<BindingResource<'_>> {
+        vec![
+            BindingResource::TextureViewArray(self.views.iter().map(|view| &view.view).collect()),
+        ]
+    }
+
+    fn resource_ids(&self) -> Vec<ResourceId> {
+        self.views.iter()
+            .flat_map(|view| view.resource_ids())
+            .collect()
+    }
+}
+
+///
+/// N buffers bound as a single arrayed storage buffer binding with count N.
+///
+/// Requires the BUFFER_BINDING_ARRAY and STORAGE_RESOURCE_BINDING_ARRAY features which can be
+/// enabled with GPUContextBuilder::enable_binding_arrays.
+///
+pub struct BufferArray<C: bytemuck::Pod, const N: usize>{
+    buffers: [Buffer<C>; N],
+}
+
+impl<C: bytemuck::Pod, const N: usize> BufferArray<C, N>{
+    ///
+    /// Creates a BufferArray.
+    /// Returns an error if the BUFFER_BINDING_ARRAY or STORAGE_RESOURCE_BINDING_ARRAY features
+    /// are not enabled on the device.
+    ///
+    pub fn new(device: &wgpu::Device, buffers: [Buffer<C>; N]) -> Result<Self>{
+        ensure!(device.features().contains(wgpu::Features::BUFFER_BINDING_ARRAY | wgpu::Features::STORAGE_RESOURCE_BINDING_ARRAY),
+            "BufferArray requires the BUFFER_BINDING_ARRAY and STORAGE_RESOURCE_BINDING_ARRAY features");
+        ensure!(N > 0, "BufferArray requires at least one buffer");
+
+        Ok(Self{
+            buffers,
+        })
+    }
+
+    #[inline]
+    pub fn buffers(&self) -> &[Buffer<C>]{
+        &self.buffers
+    }
+}
+
+impl<C: bytemuck::Pod, const N: usize> BindGroupContent for BufferArray<C, N>{
+    const BINDING_COUNT: u32 = 1;
+
+    fn entries(visibility: Option<wgpu::ShaderStages>) -> Vec<BindGroupLayoutEntry> {
+        Buffer::<C>::entries(visibility).into_iter()
+            .map(|entry| BindGroupLayoutEntry{
+                count: NonZeroU32::new(N as u32),
+                ..entry
+            })
+            .collect()
+    }
+
+    fn resources(&self) -> Vec<BindingResource<'_>> {
+        let bindings = self.buffers.iter()
+            .map(|buffer| wgpu::BufferBinding{
+                buffer: &buffer.buffer,
+                offset: 0,
+                size: None,
+            })
+            .collect();
+        vec![
+            BindingResource::BufferArray(bindings),
+        ]
+    }
+
+    fn resource_ids(&self) -> Vec<ResourceId> {
+        self.buffers.iter()
+            .map(|buffer| buffer.id())
+            .collect()
+    }
+}
@@ -2,7 +2,9 @@ use ewgpu_macros::DerefMut;
 use wgpu::util::DeviceExt;
 use std::{marker::PhantomData, ops::{Deref, DerefMut, RangeBounds, Range}};
 use std::mem::ManuallyDrop;
+use anyhow::{Result, ensure};
 use crate::utils::*;
+use crate::texture::{Texture, IntoExtent3D};
 
 use super::binding;
 
@@ -32,41 +34,57 @@ impl<'bs, C: bytemuck::Pod> BufferSlice<'bs, C>{
         ((self.range.start * std::mem::size_of::<C>()) as u64)..((self.range.end * std::mem::size_of::<C>()) as u64)
     }
 
+    ///
+    /// Returns the number of elements in the slice.
+    ///
+    #[inline]
+    pub fn len(&self) -> usize{
+        self.range.end - self.range.start
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool{
+        self.range.is_empty()
+    }
+
     ///
     /// Map the slice whilst polling the device.
+    /// Returns an error if the slice could not be mapped.
     ///
-    pub async fn map_async_poll(&self, device: &wgpu::Device) -> BufferView<'bs, C>{
+    pub async fn map_async_poll(&self, device: &wgpu::Device) -> Result<BufferView<'bs, C>>{
         let slice = self.buffer.buffer.slice(self.range_addr());
         let mapping = slice.map_async(wgpu::MapMode::Read);
 
         device.poll(wgpu::Maintain::Wait);
 
-        mapping.await.unwrap();
+        mapping.await?;
 
-        BufferView{
+        Ok(BufferView{
             buffer: self.buffer,
             buffer_view: ManuallyDrop::new(slice.get_mapped_range()),
-        }
+        })
     }
 
     ///
     /// Map the slice asynchronously.
     /// wgpu::Device::poll has to be called before this Future will complete.
+    /// Returns an error if the slice could not be mapped.
     ///
-    pub async fn map_async(&self) -> BufferView<'bs, C>{
+    pub async fn map_async(&self) -> Result<BufferView<'bs, C>>{
         let slice = self.buffer.buffer.slice(self.range_addr());
         let mapping = slice.map_async(wgpu::MapMode::Read);
 
-        mapping.await.unwrap();
+        mapping.await?;
 
-        BufferView{
+        Ok(BufferView{
             buffer: self.buffer,
             buffer_view: ManuallyDrop::new(slice.get_mapped_range()),
-        }
+        })
     }
 
     ///
     /// Map the slice and block this thread untill maping is complete.
+    /// Panics if the slice could not be mapped, see try_map_blocking.
     ///
     /// Example:
     /// 
@@ -78,12 +96,24 @@ impl<'bs, C: bytemuck::Pod> BufferSlice<'bs, C>{
     /// let i = mapped_buffer.slice(..)[0];
     /// 
     pub fn map_blocking(&self, device: &wgpu::Device) -> BufferView<'bs, C>{
+        self.try_map_blocking(device)
+            .unwrap_or_else(|err| panic!("Failed to map buffer: {}", err))
+    }
+
+    ///
+    /// Map the slice and block this thread untill maping is complete.
+    /// Returns an error if the slice could not be mapped.
+    ///
+    pub fn try_map_blocking(&self, device: &wgpu::Device) -> Result<BufferView<'bs, C>>{
         pollster::block_on(self.map_async_poll(device))
     }
 
     ///
-    /// Copy the buffer slice to another buffer.
-    /// Only the bytes that fit into the destination buffer are copied.
+    /// Copy the buffer slice to another buffer starting at offset elements.
+    ///
+    /// Returns an error if the slice does not fit into the destination, the source is missing
+    /// COPY_SRC or the destination COPY_DST usage or the copy is not aligned to
+    /// COPY_BUFFER_ALIGNMENT.
     ///
     /// ```rust
     /// use ewgpu::*;
@@ -107,7 +137,8 @@ impl<'bs, C: bytemuck::Pod> BufferSlice<'bs, C>{
     ///                 .build(&gpu.device, &[0, 0, 0, 0]);
     ///
     /// gpu.encode(|gpu, encoder|{
-    ///     buffer1.slice(..).copy_to_buffer(&mut buffer2, 1, encoder);
+    ///     buffer1.slice(..3).copy_to_buffer(&mut buffer2, 1, encoder).unwrap();
+    ///     assert!(buffer1.slice(..).copy_to_buffer(&mut buffer2, 1, encoder).is_err());
     /// });
     ///
     /// gpu.encode(|gpu, encoder|{
@@ -115,23 +146,142 @@ impl<'bs, C: bytemuck::Pod> BufferSlice<'bs, C>{
     /// })
     /// ```
     ///
-    pub fn copy_to_buffer(&self, dst: &mut Buffer<C>, offset: wgpu::BufferAddress, encoder: &mut wgpu::CommandEncoder){
-        //let slice = self.buffer.buffer.slice(self.range_bytes);
+    pub fn copy_to_buffer(&self, dst: &mut Buffer<C>, offset: wgpu::BufferAddress, encoder: &mut wgpu::CommandEncoder) -> Result<()>{
+        self.copy_to_buffer_as(dst, offset, encoder)
+    }
+
+    ///
+    /// Copy the buffer slice to a buffer of another element type with the same size starting at
+    /// offset elements.
+    /// Returns the same errors as copy_to_buffer.
+    ///
+    pub fn copy_to_buffer_as<D: bytemuck::Pod>(&self, dst: &mut Buffer<D>, offset: wgpu::BufferAddress, encoder: &mut wgpu::CommandEncoder) -> Result<()>{
+        ensure!(
+            std::mem::size_of::<C>() == std::mem::size_of::<D>(),
+            "Cannot copy elements of {} bytes to a buffer with elements of {} bytes",
+            std::mem::size_of::<C>(), std::mem::size_of::<D>()
+        );
+        ensure!(
+            self.buffer.usage.contains(wgpu::BufferUsages::COPY_SRC),
+            "Source buffer {:?} does not have COPY_SRC usage", self.buffer.label
+        );
+        ensure!(
+            dst.usage.contains(wgpu::BufferUsages::COPY_DST),
+            "Destination buffer {:?} does not have COPY_DST usage", dst.label
+        );
+        ensure!(
+            usize::try_from(offset).ok()
+                .and_then(|offset| offset.checked_add(self.len()))
+                .is_some_and(|end| end <= dst.len()),
+            "Copying {} elements to offset {} of buffer {:?} with {} elements",
+            self.len(), offset, dst.label, dst.len()
+        );
+
         let range_addr = self.range_addr();
-        let src_offset_bytes = range_addr.start as u64;
+        let size_bytes = range_addr.end - range_addr.start;
+        let dst_offset_bytes = offset * std::mem::size_of::<D>() as u64;
+        ensure!(
+            range_addr.start.is_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT)
+                && dst_offset_bytes.is_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT)
+                && size_bytes.is_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT),
+            "Copy of {} bytes from offset {} to offset {} is not aligned to {} bytes",
+            size_bytes, range_addr.start, dst_offset_bytes, wgpu::COPY_BUFFER_ALIGNMENT
+        );
+
+        if size_bytes > 0{
+            encoder.copy_buffer_to_buffer(
+                &self.buffer.buffer,
+                range_addr.start,
+                &dst.buffer,
+                dst_offset_bytes,
+                size_bytes
+            );
+        }
+        Ok(())
+    }
 
-        let dst_offset_bytes = offset * std::mem::size_of::<C>() as u64;
+    ///
+    /// Copy the buffer slice into the region of a texture at origin with extent.
+    /// The rows of the slice are bytes_per_row apart and the images are extent.height rows apart.
+    ///
+    /// Returns an error if the region is outside of the texture, the origin is not aligned to the
+    /// texel blocks of a compressed format, the slice is too small, the buffer is missing COPY_SRC
+    /// or the texture COPY_DST usage or bytes_per_row is not a multiple of
+    /// COPY_BYTES_PER_ROW_ALIGNMENT.
+    ///
+    /// ```ignore
+    /// buffer.slice(..).copy_to_texture(&texture, wgpu::Origin3d::ZERO, [64, 64], 256, encoder)?;
+    /// ```
+    ///
+    pub fn copy_to_texture(&self, dst: &Texture, origin: wgpu::Origin3d, extent: impl IntoExtent3D, bytes_per_row: u32, encoder: &mut wgpu::CommandEncoder) -> Result<()>{
+        let extent = extent.into_extent_3d();
+        ensure!(
+            self.buffer.usage.contains(wgpu::BufferUsages::COPY_SRC),
+            "Source buffer {:?} does not have COPY_SRC usage", self.buffer.label
+        );
+        ensure!(
+            dst.usage.contains(wgpu::TextureUsages::COPY_DST),
+            "Destination texture does not have COPY_DST usage"
+        );
+        ensure!(
+            origin.x.checked_add(extent.width).is_some_and(|end| end <= dst.size.width)
+                && origin.y.checked_add(extent.height).is_some_and(|end| end <= dst.size.height)
+                && origin.z.checked_add(extent.depth_or_array_layers).is_some_and(|end| end <= dst.size.depth_or_array_layers),
+            "Region {:?} at {:?} is outside of texture with size {:?}", extent, origin, dst.size
+        );
+        ensure!(
+            bytes_per_row.is_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT),
+            "bytes_per_row {} is not a multiple of {}", bytes_per_row, wgpu::COPY_BYTES_PER_ROW_ALIGNMENT
+        );
 
-        let size_bytes = (range_addr.end - range_addr.start) as u64;
-        let size_bytes = size_bytes.min(dst.size() as u64 - dst_offset_bytes);
+        let format = dst.format.describe();
+        let (block_width, block_height) = (format.block_dimensions.0 as u32, format.block_dimensions.1 as u32);
+        ensure!(
+            origin.x.is_multiple_of(block_width) && origin.y.is_multiple_of(block_height),
+            "Origin {:?} is not aligned to the texel blocks of {}x{} of {:?}", origin, block_width, block_height, dst.format
+        );
+        let row_bytes = extent.width.div_ceil(block_width) as u64 * format.block_size as u64;
+        let rows = extent.height.div_ceil(block_height) as u64;
+        ensure!(
+            row_bytes <= bytes_per_row as u64,
+            "Rows of {} bytes do not fit into bytes_per_row {}", row_bytes, bytes_per_row
+        );
+
+        let range_addr = self.range_addr();
+        ensure!(
+            range_addr.start.is_multiple_of(format.block_size as u64),
+            "Offset {} is not a multiple of the texel block size {}", range_addr.start, format.block_size
+        );
+        let required = if rows == 0 || extent.depth_or_array_layers == 0{
+            0
+        } else{
+            bytes_per_row as u64 * rows * (extent.depth_or_array_layers as u64 - 1)
+                + bytes_per_row as u64 * (rows - 1)
+                + row_bytes
+        };
+        ensure!(
+            required <= range_addr.end - range_addr.start,
+            "Copying {:?} requires {} bytes but the slice only has {}", extent, required, range_addr.end - range_addr.start
+        );
 
-        encoder.copy_buffer_to_buffer(
-            &self.buffer.buffer,
-            src_offset_bytes,
-            &dst.buffer,
-            dst_offset_bytes,
-            size_bytes
+        encoder.copy_buffer_to_texture(
+            wgpu::ImageCopyBuffer{
+                buffer: &self.buffer.buffer,
+                layout: wgpu::ImageDataLayout{
+                    offset: range_addr.start,
+                    bytes_per_row: std::num::NonZeroU32::new(bytes_per_row),
+                    rows_per_image: std::num::NonZeroU32::new(rows as u32),
+                },
+            },
+            wgpu::ImageCopyTexture{
+                texture: &dst.texture,
+                mip_level: 0,
+                origin,
+                aspect: wgpu::TextureAspect::All,
+            },
+            extent,
         );
+        Ok(())
     }
 }
 
@@ -153,7 +303,7 @@ impl<'bs, C: bytemuck::Pod> BufferSliceMut<'bs, C>{
     /// Map the slice mutably whilst polling the device.
     ///
     ///
-    pub async fn map_async_poll_mut(&'bs self, device: &wgpu::Device) -> BufferViewMut<'bs, C>{
+    pub async fn map_async_poll_mut(&'bs self, device: &wgpu::Device) -> Result<BufferViewMut<'bs, C>>{
         let range_addr = self.range_addr();
 
         let slice = self.buffer.buffer.slice(range_addr);
@@ -161,38 +311,48 @@ impl<'bs, C: bytemuck::Pod> BufferSliceMut<'bs, C>{
 
         device.poll(wgpu::Maintain::Wait);
 
-        mapping.await.unwrap();
+        mapping.await?;
 
-        BufferViewMut{
+        Ok(BufferViewMut{
             buffer: self.buffer,
             buffer_view: ManuallyDrop::new(slice.get_mapped_range_mut()),
-        }
+        })
     }
 
     ///
     /// Map the slice asynchronously for writing to the buffer.
     /// wgpu::Device::poll has to be called before this Future will complete.
     ///
-    pub async fn map_async_mut(&'bs self) -> BufferViewMut<'bs, C>{
+    pub async fn map_async_mut(&'bs self) -> Result<BufferViewMut<'bs, C>>{
         let range_addr = self.range_addr();
 
         let slice = self.buffer.buffer.slice(range_addr);
         let mapping = slice.map_async(wgpu::MapMode::Write);
 
-        mapping.await.unwrap();
+        mapping.await?;
 
-        BufferViewMut{
+        Ok(BufferViewMut{
             buffer: self.buffer,
             buffer_view: ManuallyDrop::new(slice.get_mapped_range_mut()),
-        }
+        })
     }
 
     ///
     /// Map the slice mutably and block this thread untill maping is complete.
+    /// Panics if the slice could not be mapped, see try_map_blocking_mut.
     ///
     /// slice.map_blocking_mut(device)[0] = 1;
     ///
     pub fn map_blocking_mut(&'bs self, device: &wgpu::Device) -> BufferViewMut<'bs, C>{
+        self.try_map_blocking_mut(device)
+            .unwrap_or_else(|err| panic!("Failed to map buffer: {}", err))
+    }
+
+    ///
+    /// Map the slice mutably and block this thread untill maping is complete.
+    /// Returns an error if the slice could not be mapped.
+    ///
+    pub fn try_map_blocking_mut(&'bs self, device: &wgpu::Device) -> Result<BufferViewMut<'bs, C>>{
         pollster::block_on(self.map_async_poll_mut(device))
     }
 }
@@ -264,6 +424,14 @@ impl<'bb, C: bytemuck::Pod> BufferBuilder<'bb, C>{
         self
     }
     ///
+    /// Set the INDIRECT usage.
+    ///
+    #[inline]
+    pub fn indirect(mut self) -> Self{
+        self.usages |= wgpu::BufferUsages::INDIRECT;
+        self
+    }
+    ///
     /// Set the STORAGE usage.
     ///
     #[inline]
@@ -348,6 +516,13 @@ impl<'bb, C: bytemuck::Pod> BufferBuilder<'bb, C>{
     pub fn build_empty(&self, device: &wgpu::Device, len: usize) -> Buffer<C>{
         Buffer::<C>::new_empty(device, self.usages, self.label, len)
     }
+
+    ///
+    /// Build a buffer with length that is mapped at creation and written by f.
+    ///
+    pub fn build_mapped_at_creation<F: FnOnce(&mut BufferViewMut<C>)>(&self, device: &wgpu::Device, len: usize, f: F) -> Buffer<C>{
+        Buffer::<C>::new_mapped_at_creation(device, self.usages, self.label, len, f)
+    }
 }
 
 // TODO: std140 and std430
@@ -360,6 +535,7 @@ impl<'bb, C: bytemuck::Pod> BufferBuilder<'bb, C>{
 pub struct Buffer<C: bytemuck::Pod>{
     #[target]
     pub buffer: wgpu::Buffer,
+    id: binding::ResourceId,
     len: usize,
     usage: wgpu::BufferUsages,
     label: Option<String>,
@@ -379,6 +555,7 @@ impl<C: bytemuck::Pod> Buffer<C>{
 
         Self{
             buffer,
+            id: binding::ResourceId::unique(),
             len,
             usage,
             label,
@@ -386,6 +563,56 @@ impl<C: bytemuck::Pod> Buffer<C>{
         }
     }
 
+    ///
+    /// Creates a buffer that is mapped at creation without requiring the MAP_WRITE usage.
+    /// Its content is written through the view passed to f, the buffer is unmapped when f returns
+    /// so that it can be used right away.
+    ///
+    /// ```rust
+    /// # use ewgpu::*;
+    /// # let gpu = GPUContextBuilder::new()
+    /// #   .set_features_util()
+    /// #   .build();
+    ///
+    /// let buffer = Buffer::<u32>::new_mapped_at_creation(&gpu.device, wgpu::BufferUsages::MAP_READ, None, 4, |view|{
+    ///     view.copy_from_slice(&[0, 1, 2, 3]);
+    /// });
+    /// assert_eq!(buffer.slice(..).map_blocking(&gpu.device).as_ref(), [0, 1, 2, 3]);
+    /// ```
+    ///
+    pub fn new_mapped_at_creation<F: FnOnce(&mut BufferViewMut<C>)>(device: &wgpu::Device, usage: wgpu::BufferUsages, label: wgpu::Label, len: usize, f: F) -> Self{
+        let size = (len * std::mem::size_of::<C>()) as u64;
+        assert!(
+            size.is_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT),
+            "The size of a buffer mapped at creation has to be a multiple of {} bytes but is {}", wgpu::COPY_BUFFER_ALIGNMENT, size
+        );
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor{
+            label,
+            size,
+            usage,
+            mapped_at_creation: true,
+        });
+
+        let label = label.map(|x|{x.to_string()});
+
+        let buffer = Self{
+            buffer,
+            id: binding::ResourceId::unique(),
+            len,
+            usage,
+            label,
+            _pd: PhantomData,
+        };
+
+        // The view unmaps the buffer when it is dropped.
+        f(&mut BufferViewMut{
+            buffer: &buffer,
+            buffer_view: ManuallyDrop::new(buffer.buffer.slice(..).get_mapped_range_mut()),
+        });
+
+        buffer
+    }
+
     pub fn new(device: &wgpu::Device, usage: wgpu::BufferUsages, label: wgpu::Label, data: &[C]) -> Self{
         let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor{
             label,
@@ -397,6 +624,7 @@ impl<C: bytemuck::Pod> Buffer<C>{
 
         Self{
             buffer,
+            id: binding::ResourceId::unique(),
             len: data.len(),
             usage,
             label,
@@ -444,6 +672,15 @@ impl<C: bytemuck::Pod> Buffer<C>{
         Self::new_mapped(device, wgpu::BufferUsages::VERTEX, label, data)
     }
 
+    ///
+    /// Returns the id of the underlying wgpu::Buffer.
+    /// The id changes when the buffer is reallocated by resize, expand_to or resize_clear.
+    ///
+    #[inline]
+    pub fn id(&self) -> binding::ResourceId{
+        self.id
+    }
+
     ///
     /// Returns the number of elements in the buffer.
     ///
@@ -465,6 +702,11 @@ impl<C: bytemuck::Pod> Buffer<C>{
         self.len * std::mem::size_of::<C>()
     }
 
+    #[inline]
+    pub fn usage(&self) -> wgpu::BufferUsages{
+        self.usage
+    }
+
     // TODO: Export bound start and end to own functions.
     ///
     /// Slices have to be aligned with MAP_ALIGNMENT (8 bytes).
@@ -483,7 +725,7 @@ impl<C: bytemuck::Pod> Buffer<C>{
     ///     .build(&gpu.device, &[0, 1, 2, 3]);
     /// assert_eq!(buffer.slice(..).map_blocking(&gpu.device).as_ref(), [0, 1, 2, 3]);
     /// ```
-    pub fn slice<S: RangeBounds<usize>>(&self, bounds: S) -> BufferSlice<C>{
+    pub fn slice<S: RangeBounds<usize>>(&self, bounds: S) -> BufferSlice<'_, C>{
         let range = bounds.clamp(0..self.len());
 
         BufferSlice{
@@ -511,7 +753,7 @@ impl<C: bytemuck::Pod> Buffer<C>{
     /// assert_eq!(buffer.slice(..).map_blocking(&gpu.device).as_ref(), [8, 1, 2, 3]);
     /// ```
     ///
-    pub fn slice_mut<S: RangeBounds<usize>>(&mut self, bounds: S) -> BufferSliceMut<C>{
+    pub fn slice_mut<S: RangeBounds<usize>>(&mut self, bounds: S) -> BufferSliceMut<'_, C>{
         let range = bounds.clamp(0..self.len());
 
         BufferSliceMut{
@@ -523,7 +765,7 @@ impl<C: bytemuck::Pod> Buffer<C>{
     ///
     /// Expands a Buffer to a given size.
     /// This copies the content of the buffer to the new one.
-    /// If the buffer is in a BindGroup this has to be updated.
+    /// A Bound containing the buffer recreates its BindGroup when it is next used.
     ///
     pub fn expand_to(&mut self, len: usize, encoder: &mut wgpu::CommandEncoder, device: &wgpu::Device){
         if len > self.len(){
@@ -534,7 +776,7 @@ impl<C: bytemuck::Pod> Buffer<C>{
     ///
     /// Resizes a Buffer to a given size.
     /// This copies the content of the buffer cutting the excess.
-    /// If the buffer is in a BindGroup this has to be updated.
+    /// A Bound containing the buffer recreates its BindGroup when it is next used.
     ///
     pub fn resize(&mut self, len: usize, encoder: &mut wgpu::CommandEncoder, device: &wgpu::Device){
         // Need to allow manual_map because we cannot use map as it would return a ref to a value
@@ -545,14 +787,16 @@ impl<C: bytemuck::Pod> Buffer<C>{
             None => None
         };
         let mut tmp_buf = Buffer::<C>::new_empty(device, self.usage, label, len);
-        self.slice(..).copy_to_buffer(&mut tmp_buf, 0, encoder);
+        self.slice(..len.min(self.len))
+            .copy_to_buffer(&mut tmp_buf, 0, encoder)
+            .unwrap_or_else(|err| panic!("Failed to resize buffer: {}", err));
         *self = tmp_buf;
     }
 
     ///
     /// Expands a Buffer to a given size and clears it.
     /// This does not copy the content of the buffer.
-    /// If the buffer is in a BindGroup this has to be updated.
+    /// A Bound containing the buffer recreates its BindGroup when it is next used.
     ///
     pub fn expand_to_clear(&mut self, len: usize, device: &wgpu::Device){
         if len > self.len(){
@@ -563,7 +807,7 @@ impl<C: bytemuck::Pod> Buffer<C>{
     ///
     /// Resizes a Buffer to a given size and clears it.
     /// This does not copy the content of the buffer.
-    /// If the buffer is in a BindGroup this has to be updated.
+    /// A Bound containing the buffer recreates its BindGroup when it is next used.
     ///
     pub fn resize_clear(&mut self, len: usize, device: &wgpu::Device){
         // Need to allow manual_map because we cannot use map as it would return a ref to a value
@@ -583,19 +827,33 @@ impl<C: bytemuck::Pod> Buffer<C>{
 }
 
 impl<C: bytemuck::Pod> binding::BindGroupContent for Buffer<C>{
+    const BINDING_COUNT: u32 = 1;
+
     fn entries(visibility: Option<wgpu::ShaderStages>) -> Vec<binding::BindGroupLayoutEntry>{
         vec!{
             binding::BindGroupLayoutEntry::new(visibility.unwrap_or(wgpu::ShaderStages::all()), binding::wgsl::buffer(false))
         }
     }
 
-    fn resources(&self) -> Vec<wgpu::BindingResource> {
+    fn resources(&self) -> Vec<binding::BindingResource<'_>> {
         vec!{
-            self.as_entire_binding(),
+            self.as_entire_binding().into(),
+        }
+    }
+
+    fn resource_ids(&self) -> Vec<binding::ResourceId> {
+        vec!{
+            self.id,
         }
     }
 }
 
+impl<C: bytemuck::Pod> binding::BindingSize for Buffer<C>{
+    fn binding_size() -> u64 {
+        std::mem::size_of::<C>() as u64
+    }
+}
+
 pub struct BufferView<'mbr, C: bytemuck::Pod>{
     buffer: &'mbr Buffer<C>,
     buffer_view: ManuallyDrop<wgpu::BufferView<'mbr>>,
@@ -667,21 +925,31 @@ impl<'mbr, C: bytemuck::Pod> Drop for BufferViewMut<'mbr, C>{
     }
 }
 
-pub trait IndexFormat{
+///
+/// A trait implemented for types that can be used as indices in an index buffer.
+///
+pub trait IndexFormat: bytemuck::Pod{
     fn index_format() -> wgpu::IndexFormat;
-    fn get_index_format(&self) -> wgpu::IndexFormat{
-        Self::index_format()
-    }
 }
 
-impl IndexFormat for Buffer<u32>{
+impl IndexFormat for u32{
     fn index_format() -> wgpu::IndexFormat {
         wgpu::IndexFormat::Uint32
     }
 }
 
-impl IndexFormat for Buffer<u16>{
+impl IndexFormat for u16{
     fn index_format() -> wgpu::IndexFormat {
         wgpu::IndexFormat::Uint16
     }
 }
+
+impl<I: IndexFormat> Buffer<I>{
+    ///
+    /// Returns the index format of the elements in this buffer.
+    ///
+    #[inline]
+    pub fn index_format(&self) -> wgpu::IndexFormat{
+        I::index_format()
+    }
+}
@@ -0,0 +1,319 @@
+use std::marker::PhantomData;
+use std::ops::Range;
+use crate::*;
+
+///
+/// A first fit free list over the elements of one backing buffer.
+/// Free ranges are kept sorted and coalesced.
+///
+#[derive(Debug)]
+struct FreeList{
+    free: Vec<Range<usize>>,
+}
+
+impl FreeList{
+    fn new(len: usize) -> Self{
+        #[allow(clippy::single_range_in_vec_init)]
+        Self{
+            free: vec![0..len],
+        }
+    }
+
+    ///
+    /// Allocates len elements starting at a multiple of align.
+    ///
+    fn alloc(&mut self, len: usize, align: usize) -> Option<usize>{
+        let (i, start) = self.free.iter()
+            .enumerate()
+            .find_map(|(i, range)|{
+                let start = range.start.div_ceil(align) * align;
+                (start + len <= range.end).then_some((i, start))
+            })?;
+
+        let range = self.free.remove(i);
+        let mut index = i;
+        if range.start < start{
+            self.free.insert(index, range.start..start);
+            index += 1;
+        }
+        if start + len < range.end{
+            self.free.insert(index, start + len..range.end);
+        }
+        Some(start)
+    }
+
+    fn free(&mut self, range: Range<usize>){
+        let i = self.free.partition_point(|free| free.start < range.start);
+        self.free.insert(i, range);
+
+        if i + 1 < self.free.len() && self.free[i].end == self.free[i + 1].start{
+            let next = self.free.remove(i + 1);
+            self.free[i].end = next.end;
+        }
+        if i > 0 && self.free[i - 1].end == self.free[i].start{
+            let current = self.free.remove(i);
+            self.free[i - 1].end = current.end;
+        }
+    }
+
+    fn free_len(&self) -> usize{
+        self.free.iter().map(|range| range.len()).sum()
+    }
+
+    fn largest_free(&self) -> usize{
+        self.free.iter().map(|range| range.len()).max().unwrap_or(0)
+    }
+}
+
+struct Block<C: bytemuck::Pod>{
+    buffer: Buffer<C>,
+    free_list: FreeList,
+}
+
+///
+/// A range of elements inside one of the backing buffers of a BufferArena.
+///
+/// Allocations have to be returned with BufferArena::free, dropping them leaks their range.
+///
+#[derive(Debug)]
+pub struct BufferAllocation<C: bytemuck::Pod>{
+    arena: binding::ResourceId,
+    block: usize,
+    offset: usize,
+    len: usize,
+    reserved: usize,
+    _ty: PhantomData<C>,
+}
+
+impl<C: bytemuck::Pod> BufferAllocation<C>{
+    ///
+    /// Returns the offset of the allocation in elements of its backing buffer.
+    ///
+    #[inline]
+    pub fn offset(&self) -> usize{
+        self.offset
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize{
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool{
+        self.len == 0
+    }
+
+    ///
+    /// Returns the range of the allocation in elements of its backing buffer.
+    ///
+    #[inline]
+    pub fn range(&self) -> Range<usize>{
+        self.offset..self.offset + self.len
+    }
+}
+
+///
+/// Statistics of a BufferArena. Sizes are in elements.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BufferArenaStats{
+    pub blocks: usize,
+    pub capacity: usize,
+    pub allocations: usize,
+    /// Number of elements reserved by allocations including alignment padding.
+    pub allocated: usize,
+    pub free: usize,
+    pub largest_free: usize,
+    /// The share of free elements outside of the largest free range of their block.
+    /// 0 if the free elements of every block are contiguous.
+    pub fragmentation: f32,
+}
+
+///
+/// A sub-allocator that places many small allocations into few large buffers.
+///
+/// Allocations start at offsets aligned to MAP_ALIGNMENT and COPY_BUFFER_ALIGNMENT, and to the
+/// storage or uniform offset alignment of the device if the arena has that usage, so that their
+/// slices can be copied, mapped and bound like slices of a Buffer.
+/// Allocations larger than the block length get a dedicated backing buffer.
+///
+/// ```ignore
+/// let mut arena = BufferArena::<Vert>::new(&gpu.device, wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST, 1 << 16, None);
+/// let mesh = arena.alloc(&gpu.device, vertices.len());
+/// arena.write(&gpu.queue, &mesh, &vertices);
+/// rpass_ppl.set_vertex_buffer(0, arena.slice(&mesh));
+/// arena.free(mesh);
+/// ```
+///
+pub struct BufferArena<C: bytemuck::Pod>{
+    id: binding::ResourceId,
+    blocks: Vec<Block<C>>,
+    block_len: usize,
+    align: usize,
+    usage: wgpu::BufferUsages,
+    label: Option<String>,
+    allocations: usize,
+}
+
+impl<C: bytemuck::Pod> BufferArena<C>{
+    pub fn new(device: &wgpu::Device, usage: wgpu::BufferUsages, block_len: usize, label: wgpu::Label) -> Self{
+        let mut align_bytes = wgpu::MAP_ALIGNMENT.max(wgpu::COPY_BUFFER_ALIGNMENT);
+        if usage.contains(wgpu::BufferUsages::STORAGE){
+            align_bytes = align_bytes.max(device.limits().min_storage_buffer_offset_alignment as u64);
+        }
+        if usage.contains(wgpu::BufferUsages::UNIFORM){
+            align_bytes = align_bytes.max(device.limits().min_uniform_buffer_offset_alignment as u64);
+        }
+
+        // The smallest number of elements whose size is a multiple of the alignment.
+        let size = std::mem::size_of::<C>().max(1);
+        let align_bytes = align_bytes as usize;
+        let align = align_bytes / gcd(size, align_bytes);
+
+        Self{
+            id: binding::ResourceId::unique(),
+            blocks: Vec::new(),
+            block_len: block_len.max(align),
+            align,
+            usage,
+            label: label.map(|label| label.to_string()),
+            allocations: 0,
+        }
+    }
+
+    ///
+    /// Returns the alignment of allocations in elements.
+    ///
+    #[inline]
+    pub fn align(&self) -> usize{
+        self.align
+    }
+
+    ///
+    /// Allocates len elements, creating a new backing buffer if no free range is large enough.
+    ///
+    pub fn alloc(&mut self, device: &wgpu::Device, len: usize) -> BufferAllocation<C>{
+        // Padding the length keeps the size of every reserved range a multiple of the alignment.
+        let reserved = len.max(1).div_ceil(self.align) * self.align;
+
+        let found = self.blocks.iter_mut()
+            .enumerate()
+            .find_map(|(i, block)| block.free_list.alloc(reserved, self.align).map(|offset| (i, offset)));
+
+        let (block, offset) = match found{
+            Some(found) => found,
+            None => {
+                let block_len = self.block_len.max(reserved);
+                let mut free_list = FreeList::new(block_len);
+                let offset = free_list.alloc(reserved, self.align)
+                    .expect("A new block fits the allocation");
+                self.blocks.push(Block{
+                    buffer: Buffer::new_empty(device, self.usage, self.label.as_deref(), block_len),
+                    free_list,
+                });
+                (self.blocks.len() - 1, offset)
+            }
+        };
+
+        self.allocations += 1;
+        BufferAllocation{
+            arena: self.id,
+            block,
+            offset,
+            len,
+            reserved,
+            _ty: PhantomData,
+        }
+    }
+
+    ///
+    /// Returns the range of the allocation to the arena.
+    /// Panics if the allocation belongs to another arena.
+    ///
+    pub fn free(&mut self, allocation: BufferAllocation<C>){
+        assert_eq!(allocation.arena, self.id, "BufferAllocation freed in an arena it was not allocated in");
+        self.blocks[allocation.block].free_list.free(allocation.offset..allocation.offset + allocation.reserved);
+        self.allocations -= 1;
+    }
+
+    ///
+    /// Returns the backing buffer of the allocation.
+    ///
+    pub fn buffer(&self, allocation: &BufferAllocation<C>) -> &Buffer<C>{
+        assert_eq!(allocation.arena, self.id, "BufferAllocation used with an arena it was not allocated in");
+        &self.blocks[allocation.block].buffer
+    }
+
+    pub fn slice(&self, allocation: &BufferAllocation<C>) -> BufferSlice<'_, C>{
+        self.buffer(allocation).slice(allocation.range())
+    }
+
+    pub fn slice_mut(&mut self, allocation: &BufferAllocation<C>) -> BufferSliceMut<'_, C>{
+        assert_eq!(allocation.arena, self.id, "BufferAllocation used with an arena it was not allocated in");
+        self.blocks[allocation.block].buffer.slice_mut(allocation.range())
+    }
+
+    ///
+    /// Queues a write of data to the start of the allocation.
+    /// Panics if the data does not fit into the allocation.
+    ///
+    pub fn write(&mut self, queue: &wgpu::Queue, allocation: &BufferAllocation<C>, data: &[C]){
+        assert!(data.len() <= allocation.len, "Writing {} elements to an allocation of {}", data.len(), allocation.len);
+        assert_eq!(allocation.arena, self.id, "BufferAllocation used with an arena it was not allocated in");
+        self.blocks[allocation.block].buffer.write_buffer(queue, allocation.offset, data);
+    }
+
+    pub fn stats(&self) -> BufferArenaStats{
+        let capacity = self.blocks.iter().map(|block| block.buffer.len()).sum();
+        let free = self.blocks.iter().map(|block| block.free_list.free_len()).sum();
+        let largest_free = self.blocks.iter().map(|block| block.free_list.largest_free()).max().unwrap_or(0);
+        let contiguous_free: usize = self.blocks.iter().map(|block| block.free_list.largest_free()).sum();
+        BufferArenaStats{
+            blocks: self.blocks.len(),
+            capacity,
+            allocations: self.allocations,
+            allocated: capacity - free,
+            free,
+            largest_free,
+            fragmentation: if free == 0 {0.} else {1. - contiguous_free as f32 / free as f32},
+        }
+    }
+}
+
+fn gcd(a: usize, b: usize) -> usize{
+    if b == 0 {a} else {gcd(b, a % b)}
+}
+
+#[cfg(test)]
+mod test{
+    use super::*;
+
+    #[test]
+    fn free_list_alloc_aligned(){
+        let mut free_list = FreeList::new(64);
+        assert_eq!(free_list.alloc(3, 4), Some(0));
+        assert_eq!(free_list.alloc(4, 4), Some(4));
+        assert_eq!(free_list.free, vec![3..4, 8..64]);
+        assert_eq!(free_list.alloc(60, 4), None);
+        assert_eq!(free_list.alloc(56, 4), Some(8));
+        assert_eq!(free_list.free, vec![3..4]);
+    }
+
+    #[test]
+    fn free_list_coalesce(){
+        let mut free_list = FreeList::new(16);
+        let a = free_list.alloc(4, 1).unwrap();
+        let b = free_list.alloc(4, 1).unwrap();
+        let c = free_list.alloc(4, 1).unwrap();
+
+        free_list.free(a..a + 4);
+        free_list.free(c..c + 4);
+        assert_eq!(free_list.free, vec![0..4, 8..16]);
+        assert_eq!(free_list.largest_free(), 8);
+
+        free_list.free(b..b + 4);
+        assert_eq!(free_list.free, vec![0..16]);
+        assert_eq!(free_list.free_len(), 16);
+    }
+}
@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use crate::*;
+
+///
+/// A wrapper for wgpu::Device that caches objects scoped to the device.
+///
+/// BindGroupLayouts are cached by their entries so that all BindGroupContents with the same
+/// entries share one layout. Bind groups created by into_bound are therefore compatible with
+/// pipeline layouts created by pipeline_layout!.
+///
+#[derive(DerefMut)]
+pub struct Device{
+    #[target]
+    pub device: wgpu::Device,
+    bind_group_layouts: Mutex<HashMap<Vec<wgpu::BindGroupLayoutEntry>, Arc<wgpu::BindGroupLayout>>>,
+}
+
+impl Device{
+    pub fn new(device: wgpu::Device) -> Self{
+        Self{
+            device,
+            bind_group_layouts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    ///
+    /// Returns the BindGroupLayout with the entries, creating it if it has not been created on
+    /// this device yet.
+    /// The label is only used when the layout is created.
+    ///
+    pub fn bind_group_layout(&self, entries: &[wgpu::BindGroupLayoutEntry], label: wgpu::Label) -> Arc<wgpu::BindGroupLayout>{
+        let mut bind_group_layouts = self.bind_group_layouts.lock().unwrap();
+        if let Some(layout) = bind_group_layouts.get(entries){
+            return layout.clone();
+        }
+        let layout = Arc::new(self.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor{
+            entries,
+            label,
+        }));
+        bind_group_layouts.insert(entries.to_vec(), layout.clone());
+        layout
+    }
+
+    ///
+    /// Returns the number of cached BindGroupLayouts.
+    ///
+    pub fn bind_group_layout_count(&self) -> usize{
+        self.bind_group_layouts.lock().unwrap().len()
+    }
+}
+
+impl From<wgpu::Device> for Device{
+    fn from(device: wgpu::Device) -> Self{
+        Self::new(device)
+    }
+}
@@ -1,3 +1,4 @@
+use std::sync::Arc;
 
 use std::time::{Instant, Duration};
 use crate::*;
@@ -105,6 +106,22 @@ impl<'gcb> GPUContextBuilder<'gcb>{
             .enable_feature(wgpu::Features::POLYGON_MODE_LINE)
     }
 
+    ///
+    /// Enables the features required by TextureArray and BufferArray.
+    ///
+    pub fn enable_binding_arrays(self) -> Self{
+        self.enable_feature(wgpu::Features::TEXTURE_BINDING_ARRAY)
+            .enable_feature(wgpu::Features::BUFFER_BINDING_ARRAY)
+            .enable_feature(wgpu::Features::STORAGE_RESOURCE_BINDING_ARRAY)
+    }
+
+    ///
+    /// Enables indexing of texture and storage buffer arrays with non uniform indices.
+    ///
+    pub fn enable_non_uniform_indexing(self) -> Self{
+        self.enable_feature(wgpu::Features::SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING)
+    }
+
     pub fn set_features_default(mut self) -> Self{
         self.device_descriptor.features = wgpu::Features::default();
         self
@@ -130,6 +147,16 @@ impl<'gcb> GPUContextBuilder<'gcb>{
         self
     }
 
+    ///
+    /// Panics with the missing features if the adapter does not support all enabled features.
+    ///
+    fn check_features(&self, adapter: &wgpu::Adapter){
+        let missing = self.device_descriptor.features - adapter.features();
+        if !missing.is_empty(){
+            panic!("The adapter does not support the enabled features: {:?}", missing);
+        }
+    }
+
     pub fn build_with_instance(&self, instance: wgpu::Instance) -> GPUContext{
         pollster::block_on(self.build_with_instance_async(instance))
     }
@@ -139,14 +166,17 @@ impl<'gcb> GPUContextBuilder<'gcb>{
             &self.request_adapter_options
         ).await.unwrap();
 
+        self.check_features(&adapter);
+
         let (device, queue) = adapter.request_device(
             &self.device_descriptor,
             None,
         ).await.unwrap();
 
         GPUContext{
-            device,
+            device: Arc::new(Device::new(device)),
             queue,
+            pipeline_cache: PipelineCache::new(),
             adapter,
             instance,
             time: Instant::now(),
@@ -174,14 +204,17 @@ impl<'gcb> GPUContextBuilder<'gcb>{
             &self.request_adapter_options
         ).await.unwrap();
 
+        self.check_features(&adapter);
+
         let (device, queue) = adapter.request_device(
             &self.device_descriptor,
             None,
         ).await.unwrap();
 
         GPUContext{
-            device,
+            device: Arc::new(Device::new(device)),
             queue,
+            pipeline_cache: PipelineCache::new(),
             adapter,
             instance,
             time: Instant::now(),
@@ -192,8 +225,10 @@ impl<'gcb> GPUContextBuilder<'gcb>{
 }
 
 pub struct GPUContext{
-    pub device: wgpu::Device,
+    /// Dereferences to the wgpu::Device.
+    pub device: Arc<Device>,
     pub queue: wgpu::Queue,
+    pub pipeline_cache: PipelineCache,
     pub adapter: wgpu::Adapter,
     pub instance: wgpu::Instance,
     pub time: Instant,
@@ -237,8 +272,9 @@ impl GPUContext{
 
         // DT is initialized with 1 second for first frame
         Self{
-            device,
+            device: Arc::new(Device::new(device)),
             queue,
+            pipeline_cache: PipelineCache::new(),
             adapter,
             instance,
             time: Instant::now(),
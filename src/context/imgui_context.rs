@@ -109,7 +109,7 @@ impl ImguiContext{
         }
     }
 
-    pub fn handle_events(&mut self, winit: &WinitContext, event: &Event<()>) -> UpdatedImguiContext{
+    pub fn handle_events(&mut self, winit: &WinitContext, event: &Event<()>) -> UpdatedImguiContext<'_>{
             self.platform.handle_event(self.context.io_mut(), &winit.window, event);
             UpdatedImguiContext{
                 imgui: self
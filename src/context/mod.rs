@@ -1,3 +1,4 @@
+pub mod device;
 
 pub mod gpu_context;
 pub mod winit_context;
@@ -6,6 +7,7 @@ pub mod imgui_context;
 #[cfg(feature = "egui")]
 pub mod egui_context;
 
+pub use device::*;
 pub use gpu_context::*;
 pub use winit_context::*;
 #[cfg(feature = "imgui")]
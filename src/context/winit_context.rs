@@ -144,7 +144,7 @@ impl WinitContext{
     ///
     /// ```
     ///
-    pub fn handle_events(&mut self, event: &Event<()>, control_flow: &mut ControlFlow) -> UpdatedWinitContext
+    pub fn handle_events(&mut self, event: &Event<()>, control_flow: &mut ControlFlow) -> UpdatedWinitContext<'_>
     {
         match *event{
             Event::WindowEvent{
@@ -185,7 +185,7 @@ impl Deref for WinitContext{
     }
 }
 
-impl<'w> DerefMut for WinitContext{
+impl DerefMut for WinitContext{
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.gpu_context
     }
@@ -0,0 +1,260 @@
+use std::ops::{Deref, RangeBounds};
+use crate::*;
+use crate::utils::*;
+
+///
+/// A growable array on the GPU with a length that is separate from the capacity of its Buffer.
+///
+/// Writes are queued with wgpu::Queue::write_buffer and executed at the next submit.
+/// When the capacity is exceeded the buffer is reallocated with at least double the capacity
+/// and the content is copied in a command buffer that is submitted immediately, so that writes
+/// queued before and after the reallocation end up in the right buffer.
+///
+/// Every reallocation increments the generation and changes the id of the buffer, so a Bound
+/// containing the GpuVec recreates its BindGroup when it is next used.
+///
+/// Only the elements up to the length are bound, so that .length() of a runtime sized array in
+/// a shader returns the length of the GpuVec. As bindings cannot be empty an empty GpuVec binds
+/// its first element, so .length() returns 1 for it. Changing the length also recreates the
+/// BindGroup of a Bound containing it.
+///
+/// GPU copies and writes have to be aligned to wgpu::COPY_BUFFER_ALIGNMENT bytes. For elements
+/// smaller than that, such as u16, the capacity is rounded up and writes are padded with zeroed
+/// elements, but a GpuVec can only be extended while its length in bytes is a multiple of
+/// wgpu::COPY_BUFFER_ALIGNMENT, for example by pushing u16 in pairs. The binding size is rounded
+/// up the same way.
+///
+/// ```ignore
+/// let mut particles = GpuVec::<Particle>::new(&gpu.device, wgpu::BufferUsages::STORAGE, None);
+/// particles.push(&gpu.device, &gpu.queue, Particle::default());
+/// particles.extend_from_slice(&gpu.device, &gpu.queue, &spawned);
+/// ```
+///
+pub struct GpuVec<C: bytemuck::Pod>{
+    buffer: Buffer<C>,
+    len: usize,
+    generation: u64,
+    // Changes with the length so that a Bound recreates the BindGroup with the new binding size.
+    len_id: binding::ResourceId,
+}
+
+///
+/// Rounds len up to the next number of elements whose size is a multiple of
+/// wgpu::COPY_BUFFER_ALIGNMENT.
+///
+fn aligned_len<C>(len: usize) -> usize{
+    let mut len = len;
+    while !(len * std::mem::size_of::<C>()).is_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT as usize){
+        len += 1;
+    }
+    len
+}
+
+impl<C: bytemuck::Pod> GpuVec<C>{
+    ///
+    /// Creates an empty GpuVec with a capacity of one element, rounded up to
+    /// wgpu::COPY_BUFFER_ALIGNMENT bytes.
+    /// COPY_SRC and COPY_DST are added to the usage.
+    ///
+    pub fn new(device: &wgpu::Device, usage: wgpu::BufferUsages, label: wgpu::Label) -> Self{
+        Self::with_capacity(device, usage, label, 1)
+    }
+
+    ///
+    /// Creates an empty GpuVec that can hold at least capacity elements.
+    /// COPY_SRC and COPY_DST are added to the usage.
+    ///
+    pub fn with_capacity(device: &wgpu::Device, usage: wgpu::BufferUsages, label: wgpu::Label, capacity: usize) -> Self{
+        let usage = usage | wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST;
+        Self{
+            buffer: Buffer::new_empty(device, usage, label, aligned_len::<C>(capacity.max(1))),
+            len: 0,
+            generation: 0,
+            len_id: binding::ResourceId::unique(),
+        }
+    }
+
+    pub fn from_slice(device: &wgpu::Device, queue: &wgpu::Queue, usage: wgpu::BufferUsages, label: wgpu::Label, data: &[C]) -> Self{
+        let mut vec = Self::with_capacity(device, usage, label, data.len());
+        vec.extend_from_slice(device, queue, data);
+        vec
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize{
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool{
+        self.len == 0
+    }
+
+    ///
+    /// Returns the number of elements the GpuVec can hold without reallocating.
+    ///
+    #[inline]
+    pub fn capacity(&self) -> usize{
+        self.buffer.len()
+    }
+
+    ///
+    /// Returns the number of times the buffer has been reallocated.
+    ///
+    #[inline]
+    pub fn generation(&self) -> u64{
+        self.generation
+    }
+
+    #[inline]
+    pub fn buffer(&self) -> &Buffer<C>{
+        &self.buffer
+    }
+
+    ///
+    /// Get a slice of the elements.
+    /// The bound is clamped by the length of the GpuVec.
+    ///
+    pub fn slice<S: RangeBounds<usize>>(&self, bounds: S) -> BufferSlice<'_, C>{
+        let range = bounds.clamp(0..self.len);
+        self.buffer.slice(range)
+    }
+
+    ///
+    /// Ensures that at least additional more elements fit into the GpuVec.
+    ///
+    pub fn reserve(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, additional: usize){
+        let required = self.len + additional;
+        if required > self.capacity(){
+            self.reallocate(device, queue, required.max(self.capacity() * 2));
+        }
+    }
+
+    ///
+    /// Reallocates the buffer so that its capacity matches the length.
+    ///
+    pub fn shrink_to_fit(&mut self, device: &wgpu::Device, queue: &wgpu::Queue){
+        if self.capacity() > aligned_len::<C>(self.len.max(1)){
+            self.reallocate(device, queue, self.len.max(1));
+        }
+    }
+
+    fn reallocate(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, capacity: usize){
+        // Both capacities are aligned so the copy covers whole aligned blocks.
+        let capacity = aligned_len::<C>(capacity);
+        if self.len == 0{
+            self.buffer.resize_clear(capacity, device);
+        } else{
+            let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor{
+                label: Some("GpuVec reallocation"),
+            });
+            self.buffer.resize(capacity, &mut encoder, device);
+            queue.submit(Some(encoder.finish()));
+        }
+        self.generation += 1;
+    }
+
+    pub fn push(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, value: C){
+        self.extend_from_slice(device, queue, &[value]);
+    }
+
+    ///
+    /// Appends the data, reallocating the buffer if the capacity is exceeded.
+    /// Panics if the length in bytes is not a multiple of wgpu::COPY_BUFFER_ALIGNMENT, which can
+    /// only happen for elements smaller than that.
+    ///
+    pub fn extend_from_slice(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, data: &[C]){
+        if data.is_empty(){
+            return;
+        }
+        assert!(
+            aligned_len::<C>(self.len) == self.len,
+            "Cannot extend a GpuVec of {} elements of {} bytes, its size is not a multiple of {} bytes",
+            self.len, std::mem::size_of::<C>(), wgpu::COPY_BUFFER_ALIGNMENT
+        );
+        self.reserve(device, queue, data.len());
+        // The capacity is aligned so the padding fits into the buffer.
+        if aligned_len::<C>(data.len()) == data.len(){
+            self.buffer.write_buffer(queue, self.len, data);
+        } else{
+            let mut padded = data.to_vec();
+            padded.resize(aligned_len::<C>(data.len()), C::zeroed());
+            self.buffer.write_buffer(queue, self.len, &padded);
+        }
+        self.set_len(self.len + data.len());
+    }
+
+    ///
+    /// Resizes the GpuVec to len elements, filling new elements with value.
+    ///
+    pub fn resize(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, len: usize, value: C){
+        if len > self.len{
+            self.extend_from_slice(device, queue, &vec![value; len - self.len]);
+        } else{
+            self.truncate(len);
+        }
+    }
+
+    ///
+    /// Shortens the GpuVec to len elements keeping the capacity.
+    /// Has no effect if len is greater than the current length.
+    ///
+    #[inline]
+    pub fn truncate(&mut self, len: usize){
+        self.set_len(self.len.min(len));
+    }
+
+    #[inline]
+    pub fn clear(&mut self){
+        self.set_len(0);
+    }
+
+    fn set_len(&mut self, len: usize){
+        if len != self.len{
+            self.len = len;
+            self.len_id = binding::ResourceId::unique();
+        }
+    }
+}
+
+impl<C: bytemuck::Pod> Deref for GpuVec<C>{
+    type Target = Buffer<C>;
+
+    fn deref(&self) -> &Self::Target{
+        &self.buffer
+    }
+}
+
+impl<C: bytemuck::Pod> binding::BindGroupContent for GpuVec<C>{
+    const BINDING_COUNT: u32 = 1;
+
+    fn entries(visibility: Option<wgpu::ShaderStages>) -> Vec<binding::BindGroupLayoutEntry>{
+        Buffer::<C>::entries(visibility)
+    }
+
+    fn resources(&self) -> Vec<binding::BindingResource<'_>>{
+        // Bindings cannot be empty so an empty GpuVec binds its first element and .length()
+        // returns 1 in the shader.
+        let len = aligned_len::<C>(self.len.max(1));
+        vec!{
+            wgpu::BindingResource::Buffer(wgpu::BufferBinding{
+                buffer: &self.buffer.buffer,
+                offset: 0,
+                size: std::num::NonZeroU64::new((len * std::mem::size_of::<C>()) as u64),
+            }).into(),
+        }
+    }
+
+    fn resource_ids(&self) -> Vec<binding::ResourceId>{
+        vec!{
+            self.buffer.id(),
+            self.len_id,
+        }
+    }
+}
+
+impl<C: bytemuck::Pod> binding::BindingSize for GpuVec<C>{
+    fn binding_size() -> u64{
+        Buffer::<C>::binding_size()
+    }
+}
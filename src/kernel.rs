@@ -0,0 +1,129 @@
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use anyhow::Result;
+use crate::*;
+
+/// Maximum number of bind groups a Kernel keeps for previously used arguments.
+const BIND_GROUP_CACHE_SIZE: usize = 8;
+
+///
+/// A compute shader together with its pipeline.
+///
+/// The pipeline layout is generated from the type parameters: Args is bound to set 0 and Push
+/// is placed in a push constant range visible to the compute stage.
+/// Use `()` as Push for kernels without push constants.
+///
+/// Bind groups are cached by the resource ids of the arguments so that running the kernel with
+/// the same buffers again does not create a new bind group.
+///
+/// ```ignore
+/// let kernel = Kernel::<(Buffer<u32>, Buffer<u32>), u32>::new(&gpu.device, "
+///     #version 460
+///     #if COMPUTE_SHADER
+///     layout(local_size_x = 64) in;
+///     layout(set = 0, binding = 0) buffer In{ uint in_buf[]; };
+///     layout(set = 0, binding = 1) buffer Out{ uint out_buf[]; };
+///     layout(push_constant) uniform Push{ uint len; };
+///     void main(){ ... }
+///     #endif
+/// ", None)?;
+///
+/// kernel.run(&mut gpu, &(input, output), &len, len)?;
+/// ```
+///
+pub struct Kernel<Args: BindGroupContent, Push: PushConstant = ()>{
+    pub pipeline: ComputePipeline,
+    bind_groups: RefCell<Vec<BindGroup<Args>>>,
+    _ty: PhantomData<Push>,
+}
+
+impl<Args: BindGroupContent, Push: PushConstant> Kernel<Args, Push>{
+    pub fn new(device: &Device, src: &str, label: Option<&str>) -> Result<Self>{
+        let shader = ComputeShader::from_src(device, src, label)?;
+        Self::from_shader(device, &shader, label)
+    }
+
+    pub fn from_shader(device: &Device, shader: &ComputeShader, label: Option<&str>) -> Result<Self>{
+        let bind_group_layout = Args::create_bind_group_layout(device, label);
+
+        let mut layout = PipelineLayoutBuilder::new()
+            .push_bind_group(&bind_group_layout);
+        if Self::has_push_const(){
+            layout = layout.push_const_layout(Push::push_const_layout(wgpu::ShaderStages::COMPUTE));
+        }
+        let layout = layout.try_build(device, label)?;
+
+        let pipeline = ComputePipelineBuilder::new(shader)
+            .set_label(label)
+            .set_layout(&layout)
+            .build(device);
+
+        Ok(Self{
+            pipeline,
+            bind_groups: RefCell::new(Vec::new()),
+            _ty: PhantomData,
+        })
+    }
+
+    fn has_push_const() -> bool{
+        std::mem::size_of::<Push>() > 0
+    }
+
+    ///
+    /// Runs the kernel with enough invocations to cover n items and submits it to the queue.
+    ///
+    pub fn run(&self, gpu: &mut GPUContext, args: &Args, push: &Push, n: u32) -> Result<()>{
+        let mut result = Ok(());
+        gpu.encode(|gpu, encoder|{
+            result = self.encode(&gpu.device, encoder, args, push, n);
+        });
+        result
+    }
+
+    ///
+    /// Records the kernel into the encoder without submitting it.
+    ///
+    pub fn encode(&self, device: &Device, encoder: &mut wgpu::CommandEncoder, args: &Args, push: &Push, n: u32) -> Result<()>{
+        let workgroups = self.pipeline.workgroup_count([n, 1, 1])?;
+
+        let mut bind_groups = self.bind_groups.borrow_mut();
+        Self::cache_bind_group(&mut bind_groups, device, args);
+
+        let mut cpass = ComputePass::new(encoder, None);
+        let mut cpass_ppl = cpass.set_pipeline(&self.pipeline);
+
+        cpass_ppl.set_bind_group(0, &bind_groups[0], &[]);
+        if Self::has_push_const(){
+            cpass_ppl.set_push_const(0, push)?;
+        }
+        cpass_ppl.dispatch(workgroups[0], workgroups[1], workgroups[2]);
+        Ok(())
+    }
+
+    ///
+    /// Moves the bind group of the arguments to the front of the cache, creating it if
+    /// necessary. Arguments without resource ids can not be identified and always get a new
+    /// bind group.
+    ///
+    fn cache_bind_group(bind_groups: &mut Vec<BindGroup<Args>>, device: &Device, args: &Args){
+        let resource_ids = args.resource_ids();
+        let cached = if resource_ids.is_empty(){
+            None
+        } else{
+            bind_groups.iter().position(|bind_group| bind_group.resource_ids() == resource_ids)
+        };
+        let bind_group = match cached{
+            Some(i) => bind_groups.remove(i),
+            None => args.create_bind_group(device),
+        };
+        bind_groups.insert(0, bind_group);
+        bind_groups.truncate(BIND_GROUP_CACHE_SIZE);
+    }
+
+    ///
+    /// Returns the number of cached bind groups.
+    ///
+    pub fn cached_bind_groups(&self) -> usize{
+        self.bind_groups.borrow().len()
+    }
+}
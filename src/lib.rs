@@ -70,7 +70,7 @@
 //!     .build(&gpu.device, &QUAD_VERTS);
 //!
 //!
-//! let vshader = VertexShader::from_src(&gpu.device, "
+//! let program = ShaderProgram::from_src(&gpu.device, "
 //! #version 460
 //! #if VERTEX_SHADER
 //!         
@@ -87,12 +87,7 @@
 //!     gl_Position = vec4(i_pos, 0.0, 1.0);
 //! }
 //!         
-//! #endif
-//! ", None).unwrap();
-//!
-//! let fshader = FragmentShader::from_src(&gpu.device, "
-//! #version 460
-//! #if FRAGMENT_SHADER
+//! #elif FRAGMENT_SHADER
 //!         
 //! layout(location = 0) in vec2 f_pos;
 //! layout(location = 1) in vec2 f_uv;
@@ -117,7 +112,7 @@
 //!     }
 //! );
 //!
-//! let pipeline = RenderPipelineBuilder::new(&vshader, &fshader)
+//! let pipeline = RenderPipelineBuilder::from_program(&program).unwrap()
 //!     .push_vert_layout(Vert2::buffer_layout())
 //!     .push_target_replace(wgpu::TextureFormat::Rgba8Unorm)
 //!     .set_layout(&layout)
@@ -134,10 +129,10 @@
 //!         color: [1.0, 0.0, 0.0, 1.0]
 //!     };
 //!
-//!     rpass_ppl.set_push_const(0, &consts);
+//!     rpass_ppl.set_push_const(0, &consts).unwrap();
 //!     rpass_ppl.set_vertex_buffer(0, vertices.slice(..));
 //!     rpass_ppl.set_index_buffer(indices.slice(..));
-//!     rpass_ppl.draw_indexed(0..(indices.len() as u32), 0, 0..1);
+//!     rpass_ppl.draw_indexed(.., 0, 0..1).unwrap();
 //! });
 //!
 //!```
@@ -151,29 +146,45 @@ pub extern crate ewgpu_macros;
 
 //extern crate nalgebra_glm as glm;
 
+pub mod algorithms;
 pub mod binding;
+pub mod binding_array;
 pub mod buffer;
+pub mod buffer_arena;
+pub mod gpu_vec;
+pub mod kernel;
+pub mod mesh;
 pub mod pipeline;
+pub mod pipeline_cache;
 pub mod render_target;
+pub mod render_bundle;
 pub mod texture;
 pub mod uniform;
 pub mod vert;
 pub mod push_constants;
 pub mod shader;
+pub mod staging_pool;
 pub mod context;
 pub mod utils;
 
 
 pub use self::binding::*;
+pub use self::binding_array::*;
 pub use self::buffer::*;
-//pub use self::mesh::*;
+pub use self::buffer_arena::*;
+pub use self::gpu_vec::*;
+pub use self::kernel::*;
+pub use self::mesh::*;
 pub use self::pipeline::*;
+pub use self::pipeline_cache::*;
 pub use self::render_target::*;
+pub use self::render_bundle::*;
 pub use self::texture::*;
 pub use self::uniform::*;
 pub use self::vert::*;
 pub use self::push_constants::*;
 pub use self::shader::*;
+pub use self::staging_pool::*;
 pub use crate::ewgpu_macros::*;
 pub use context::*;
 
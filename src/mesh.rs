@@ -0,0 +1,79 @@
+use crate::*;
+
+///
+/// A Buffer of indices that remembers its index format and number of indices.
+///
+/// ```ignore
+/// let indices = IndexBuffer::new(&gpu.device, None, &[0u16, 1, 2]);
+///
+/// rpass_ppl.set_index_buffer(indices.slice(..));
+/// rpass_ppl.draw_indexed(.., 0, 0..1).unwrap();
+/// ```
+///
+#[derive(DerefMut)]
+pub struct IndexBuffer<I: IndexFormat = u32>{
+    #[target]
+    pub buffer: Buffer<I>,
+}
+
+impl<I: IndexFormat> IndexBuffer<I>{
+    pub fn new(device: &wgpu::Device, label: wgpu::Label, data: &[I]) -> Self{
+        Self{
+            buffer: BufferBuilder::new()
+                .index().copy_dst()
+                .set_label(label)
+                .build(device, data),
+        }
+    }
+
+    ///
+    /// Returns the number of indices in the buffer.
+    ///
+    #[inline]
+    pub fn count(&self) -> u32{
+        self.buffer.len() as u32
+    }
+}
+
+impl<I: IndexFormat> From<Buffer<I>> for IndexBuffer<I>{
+    fn from(buffer: Buffer<I>) -> Self {
+        Self{
+            buffer,
+        }
+    }
+}
+
+///
+/// A mesh consisting of a vertex buffer and a typed index buffer.
+///
+/// ```ignore
+/// let mesh = IndexedMesh::new(&gpu.device, None, &QUAD_VERTS, &QUAD_IDXS);
+///
+/// let mut rpass_ppl = rpass.set_pipeline(&pipeline);
+/// rpass_ppl.draw_mesh(&mesh, 0..1);
+/// ```
+///
+pub struct IndexedMesh<V: VertLayout, I: IndexFormat = u32>{
+    pub vertices: Buffer<V>,
+    pub indices: IndexBuffer<I>,
+}
+
+impl<V: VertLayout, I: IndexFormat> IndexedMesh<V, I>{
+    pub fn new(device: &wgpu::Device, label: wgpu::Label, vertices: &[V], indices: &[I]) -> Self{
+        Self{
+            vertices: BufferBuilder::new()
+                .vertex().copy_dst()
+                .set_label(label)
+                .build(device, vertices),
+            indices: IndexBuffer::new(device, label, indices),
+        }
+    }
+
+    ///
+    /// Returns the number of indices in the mesh.
+    ///
+    #[inline]
+    pub fn count(&self) -> u32{
+        self.indices.count()
+    }
+}
@@ -1,24 +1,167 @@
 use std::str;
 use crate::*;
+use anyhow::Result;
+use wgpu::util::RenderEncoder;
 
-use core::ops::Range;
+use core::ops::{Range, RangeBounds, Bound};
 use core::num::NonZeroU32;
 
 #[allow(unused)]
 const DEFAULT_TEXTURE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Bgra8Unorm;
 pub const DEFAULT_ENTRY_POINT: &str = "main";
 
+///
+/// A builder for a ColorTargetState.
+///
+/// Provides presets for common blend states and validates the blend state against the format.
+///
+/// ```ignore
+/// let target = ColorTarget::alpha_blending(wgpu::TextureFormat::Rgba8Unorm)
+///     .set_write_mask(wgpu::ColorWrites::COLOR);
+/// ```
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorTarget{
+    pub format: wgpu::TextureFormat,
+    pub blend: Option<wgpu::BlendState>,
+    pub write_mask: wgpu::ColorWrites,
+}
+
+impl ColorTarget{
+    ///
+    /// Creates a ColorTarget that replaces the destination without blending.
+    ///
+    pub fn new(format: wgpu::TextureFormat) -> Self{
+        Self{
+            format,
+            blend: None,
+            write_mask: wgpu::ColorWrites::ALL,
+        }
+    }
+
+    pub fn alpha_blending(format: wgpu::TextureFormat) -> Self{
+        Self::new(format).set_blend(Some(wgpu::BlendState::ALPHA_BLENDING))
+    }
+
+    pub fn premultiplied_alpha(format: wgpu::TextureFormat) -> Self{
+        Self::new(format).set_blend(Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING))
+    }
+
+    pub fn additive(format: wgpu::TextureFormat) -> Self{
+        Self::new(format).set_blend_components(wgpu::BlendComponent{
+            src_factor: wgpu::BlendFactor::One,
+            dst_factor: wgpu::BlendFactor::One,
+            operation: wgpu::BlendOperation::Add,
+        })
+    }
+
+    pub fn multiply(format: wgpu::TextureFormat) -> Self{
+        Self::new(format).set_blend_components(wgpu::BlendComponent{
+            src_factor: wgpu::BlendFactor::Dst,
+            dst_factor: wgpu::BlendFactor::Zero,
+            operation: wgpu::BlendOperation::Add,
+        })
+    }
+
+    pub fn min(format: wgpu::TextureFormat) -> Self{
+        Self::new(format).set_blend_components(wgpu::BlendComponent{
+            src_factor: wgpu::BlendFactor::One,
+            dst_factor: wgpu::BlendFactor::One,
+            operation: wgpu::BlendOperation::Min,
+        })
+    }
+
+    pub fn max(format: wgpu::TextureFormat) -> Self{
+        Self::new(format).set_blend_components(wgpu::BlendComponent{
+            src_factor: wgpu::BlendFactor::One,
+            dst_factor: wgpu::BlendFactor::One,
+            operation: wgpu::BlendOperation::Max,
+        })
+    }
+
+    #[inline]
+    pub fn set_blend(mut self, blend: Option<wgpu::BlendState>) -> Self{
+        self.blend = blend;
+        self
+    }
+
+    ///
+    /// Uses the same blend component for the color and alpha channels.
+    ///
+    #[inline]
+    pub fn set_blend_components(self, component: wgpu::BlendComponent) -> Self{
+        self.set_blend(Some(wgpu::BlendState{
+            color: component,
+            alpha: component,
+        }))
+    }
+
+    ///
+    /// Sets the channels that are written to the target.
+    ///
+    #[inline]
+    pub fn set_write_mask(mut self, write_mask: wgpu::ColorWrites) -> Self{
+        self.write_mask = write_mask;
+        self
+    }
+
+    ///
+    /// Checks that the format can be rendered to and supports blending if a blend state is set.
+    ///
+    pub fn validate(&self) -> Result<()>{
+        let desc = self.format.describe();
+        if matches!(desc.sample_type, wgpu::TextureSampleType::Depth)
+            || !desc.guaranteed_format_features.allowed_usages.contains(wgpu::TextureUsages::RENDER_ATTACHMENT){
+            return Err(anyhow::anyhow!("Format {:?} can not be used as a color target", self.format));
+        }
+        if self.blend.is_some() && !desc.guaranteed_format_features.filterable{
+            return Err(anyhow::anyhow!("Format {:?} does not support blending", self.format));
+        }
+        Ok(())
+    }
+
+    pub fn try_build(self) -> Result<wgpu::ColorTargetState>{
+        self.validate()?;
+        Ok(self.into())
+    }
+
+    pub fn build(self) -> wgpu::ColorTargetState{
+        self.try_build()
+            .unwrap_or_else(|err| panic!("Invalid color target: {}", err))
+    }
+}
+
+impl From<ColorTarget> for wgpu::ColorTargetState{
+    fn from(target: ColorTarget) -> Self{
+        Self{
+            format: target.format,
+            blend: target.blend,
+            write_mask: target.write_mask,
+        }
+    }
+}
+
+impl From<&wgpu::ColorTargetState> for ColorTarget{
+    fn from(state: &wgpu::ColorTargetState) -> Self{
+        Self{
+            format: state.format,
+            blend: state.blend,
+            write_mask: state.write_mask,
+        }
+    }
+}
+
 ///
 /// A struct representing a FragmentState.
 ///
 pub struct FragmentState<'fs>{
     pub targets: Vec<wgpu::ColorTargetState>,
     pub entry_point: &'fs str,
-    pub shader: &'fs wgpu::ShaderModule,
+    pub shader: &'fs ShaderModule,
 }
 
 impl<'fs> FragmentState<'fs>{
-    pub fn new(shader: &'fs wgpu::ShaderModule) -> Self{
+    pub fn new(shader: &'fs ShaderModule) -> Self{
         Self{
             targets: Vec::new(),
             shader,
@@ -36,17 +179,35 @@ impl<'fs> FragmentState<'fs>{
         self
     }
 
-    pub fn push_target_replace(mut self, format: wgpu::TextureFormat) -> Self{
-        self.targets.push(wgpu::ColorTargetState{
-            format,
-            blend: Some(wgpu::BlendState{
-                color: wgpu::BlendComponent::REPLACE,
-                alpha: wgpu::BlendComponent::REPLACE,
-            }),
-            write_mask: wgpu::ColorWrites::all(),
-        });
+    ///
+    /// Pushes a target that replaces the destination.
+    /// Blending is disabled so that non blendable formats can be used.
+    ///
+    pub fn push_target_replace(self, format: wgpu::TextureFormat) -> Self{
+        self.push_color_target(ColorTarget::new(format))
+    }
+
+    ///
+    /// Pushes a validated ColorTarget.
+    ///
+    /// Panics if the format does not support the target's blend state.
+    ///
+    pub fn push_color_target(mut self, color_target: ColorTarget) -> Self{
+        self.targets.push(color_target.build());
         self
     }
+
+    ///
+    /// Validates all targets of the fragment state.
+    ///
+    pub fn validate(&self) -> Result<()>{
+        self.targets.iter()
+            .enumerate()
+            .try_for_each(|(i, target)|{
+                ColorTarget::from(target).validate()
+                    .map_err(|err| anyhow::anyhow!("Color target {}: {}", i, err))
+            })
+    }
 }
 
 ///
@@ -57,11 +218,11 @@ impl<'fs> FragmentState<'fs>{
 pub struct VertexState<'vs>{
     pub vertex_buffer_layouts: Vec<wgpu::VertexBufferLayout<'vs>>,
     pub entry_point: &'vs str,
-    pub shader: &'vs wgpu::ShaderModule,
+    pub shader: &'vs ShaderModule,
 }
 
 impl<'vs> VertexState<'vs>{
-    pub fn new(shader: &'vs wgpu::ShaderModule) -> Self{
+    pub fn new(shader: &'vs ShaderModule) -> Self{
         Self{
             vertex_buffer_layouts: Vec::new(),
             entry_point: DEFAULT_ENTRY_POINT,
@@ -84,17 +245,51 @@ impl<'vs> VertexState<'vs>{
 
 /// 
 /// A wrapper for wgpu::RenderPipeline with PushConstantRanges.
+/// It also keeps the depth stencil state and color targets to validate dynamic state set in the
+/// RenderPassPipeline.
 ///
 #[derive(DerefMut)]
 pub struct RenderPipeline{
     #[target]
     pub pipeline: wgpu::RenderPipeline,
-    pub push_const_ranges: Vec<wgpu::PushConstantRange>
+    pub push_const_ranges: Vec<PushConstantRange>,
+    pub depth_stencil: Option<wgpu::DepthStencilState>,
+    pub targets: Vec<wgpu::ColorTargetState>,
+}
+
+impl RenderPipeline{
+    ///
+    /// Returns a typed slot for the push constant at index if it holds a C.
+    ///
+    pub fn push_const_slot<C: PushConstant>(&self, index: usize) -> Result<PushConstantSlot<C>>{
+        PushConstantSlot::new(&self.push_const_ranges, index)
+    }
+
+    ///
+    /// Returns true if the stencil test of this pipeline reads the stencil reference.
+    ///
+    pub fn uses_stencil_reference(&self) -> bool{
+        self.depth_stencil.as_ref()
+            .map(|depth_stencil| depth_stencil.stencil.needs_ref_value())
+            .unwrap_or(false)
+    }
+
+    ///
+    /// Returns true if any color target of this pipeline blends with the blend constant.
+    ///
+    pub fn uses_blend_constant(&self) -> bool{
+        self.targets.iter().any(|target|{
+            target.blend.map(|blend|{
+                blend.color.uses_constant() || blend.alpha.uses_constant()
+            }).unwrap_or(false)
+        })
+    }
 }
 
 pub struct PipelineLayout{
     pub layout: wgpu::PipelineLayout,
-    pub push_const_ranges: Vec<wgpu::PushConstantRange>,
+    pub push_const_ranges: Vec<PushConstantRange>,
+    cache_key: pipeline_cache::PipelineLayoutKey,
 }
 
 impl PipelineLayout{
@@ -104,32 +299,160 @@ impl PipelineLayout{
     /// Mostly for the pipeline_layout macro.
     ///
     pub fn new(device: &wgpu::Device, bind_group_layouts: &[&wgpu::BindGroupLayout], push_const_layouts: &[PushConstantLayout], label: wgpu::Label) -> Self{
+        Self::try_new(device, bind_group_layouts, push_const_layouts, label)
+            .unwrap_or_else(|err| panic!("Invalid pipeline layout: {}", err))
+    }
 
-        let mut offset = 0;
-        let push_const_ranges: Vec<wgpu::PushConstantRange> = push_const_layouts.iter()
-            .map(|x| {
-                // align to 4 bytes.
-                // TODO: write tests and use Align trait.
-                let size_aligned = (((x.size as i32 - 4)/4 + 1)*4) as u32;
-                let range = Range::<u32>{
-                    start: offset,
-                    end: offset + size_aligned,
-                };
-                offset = range.end;
-                wgpu::PushConstantRange{
-                    stages: x.stages,
-                    range,
-                }
-            }).collect();
+    ///
+    /// Create a new pipeline layout returning an error if the push constants cannot be packed
+    /// within the limits of the device.
+    ///
+    /// As the entries of the bind group layouts are unknown the layout is only identical to itself
+    /// in a PipelineCache, use try_from_layouts to share pipelines between identical layouts.
+    ///
+    pub fn try_new(device: &wgpu::Device, bind_group_layouts: &[&wgpu::BindGroupLayout], push_const_layouts: &[PushConstantLayout], label: wgpu::Label) -> Result<Self>{
+        Self::create(device, bind_group_layouts, None, push_const_layouts, label)
+    }
 
-        Self{
+    ///
+    /// Create a new pipeline layout from bind group layouts with their entries.
+    ///
+    pub fn from_layouts(device: &wgpu::Device, bind_group_layouts: &[&binding::BindGroupLayoutWithDesc], push_const_layouts: &[PushConstantLayout], label: wgpu::Label) -> Self{
+        Self::try_from_layouts(device, bind_group_layouts, push_const_layouts, label)
+            .unwrap_or_else(|err| panic!("Invalid pipeline layout: {}", err))
+    }
+
+    ///
+    /// Create a new pipeline layout from bind group layouts with their entries returning an error
+    /// if the push constants cannot be packed within the limits of the device.
+    ///
+    /// Layouts with the same bind group layout entries and push constants are identical in a
+    /// PipelineCache.
+    ///
+    pub fn try_from_layouts(device: &wgpu::Device, bind_group_layouts: &[&binding::BindGroupLayoutWithDesc], push_const_layouts: &[PushConstantLayout], label: wgpu::Label) -> Result<Self>{
+        let layouts: Vec<&wgpu::BindGroupLayout> = bind_group_layouts.iter()
+            .map(|layout| &*layout.layout)
+            .collect();
+        let entries: Vec<&[wgpu::BindGroupLayoutEntry]> = bind_group_layouts.iter()
+            .map(|layout| &layout.entries[..])
+            .collect();
+
+        Self::create(device, &layouts, Some(&entries), push_const_layouts, label)
+    }
+
+    fn create(device: &wgpu::Device, bind_group_layouts: &[&wgpu::BindGroupLayout], entries: Option<&[&[wgpu::BindGroupLayoutEntry]]>, push_const_layouts: &[PushConstantLayout], label: wgpu::Label) -> Result<Self>{
+        let packing = pack_push_consts(push_const_layouts, &device.limits())?;
+
+        let cache_key = match entries{
+            Some(entries) => pipeline_cache::PipelineLayoutKey::content(entries, &packing.ranges),
+            None => pipeline_cache::PipelineLayoutKey::Unique(binding::ResourceId::unique()),
+        };
+
+        Ok(Self{
             layout: device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor{
                 label,
-                push_constant_ranges: &push_const_ranges,
+                push_constant_ranges: &packing.wgpu_ranges,
                 bind_group_layouts,
             }),
-            push_const_ranges,
-        }
+            push_const_ranges: packing.ranges,
+            cache_key,
+        })
+    }
+
+    #[inline]
+    pub(crate) fn cache_key(&self) -> pipeline_cache::PipelineLayoutKey{
+        self.cache_key.clone()
+    }
+}
+
+impl PipelineLayout{
+    ///
+    /// Returns a typed slot for the push constant at index if it holds a C.
+    ///
+    pub fn push_const_slot<C: PushConstant>(&self, index: usize) -> Result<PushConstantSlot<C>>{
+        PushConstantSlot::new(&self.push_const_ranges, index)
+    }
+}
+
+///
+/// A PipelineLayout with named bind group slots S and push constant slots P.
+/// It is created by pipeline_layout! and dereferences to the PipelineLayout.
+///
+/// ```ignore
+/// let layout = pipeline_layout!(&gpu.device,
+///     bind_groups: {
+///         camera: Uniform<Camera>,
+///     },
+///     push_constants: {
+///         consts: Consts => wgpu::ShaderStages::FRAGMENT,
+///     }
+/// );
+///
+/// rpass_ppl.set_bind_group_slot(layout.slots.camera, &camera, &[]);
+/// rpass_ppl.set_push_const_slot(layout.push.consts, &consts);
+/// ```
+///
+/// The slot types of the expression form cannot be named. To store a layout in a struct field
+/// or return it from a function, declare it with the item form instead, which generates the
+/// slot types NameSlots and NamePushSlots and the alias Name:
+///
+/// ```ignore
+/// pipeline_layout!{
+///     pub struct CameraLayout{
+///         bind_groups: {
+///             camera: Uniform<Camera>,
+///         },
+///         push_constants: {
+///             consts: Consts => wgpu::ShaderStages::FRAGMENT,
+///         }
+///     }
+/// }
+///
+/// struct Renderer{
+///     layout: CameraLayout,
+/// }
+///
+/// let renderer = Renderer{
+///     layout: CameraLayout::new(&gpu.device),
+/// };
+/// ```
+///
+/// Only push constants take a visibility. Bind group slots use the visibility of their
+/// BindGroupContent so that their layouts match the BindGroups created by into_bound:
+///
+/// ```compile_fail
+/// use ewgpu::*;
+///
+/// pipeline_layout!{
+///     struct ComputeLayout{
+///         bind_groups: {
+///             buffer: Buffer<u32> => wgpu::ShaderStages::COMPUTE,
+///         },
+///         push_constants: {}
+///     }
+/// }
+/// ```
+///
+#[derive(DerefMut)]
+pub struct NamedPipelineLayout<S, P>{
+    #[target]
+    pub layout: PipelineLayout,
+    pub slots: S,
+    pub push: P,
+}
+
+///
+/// Implemented for the bind group slots generated by the item form of pipeline_layout!.
+///
+pub trait PipelineLayoutSlots: Sized{
+    type PushSlots;
+
+    fn create_layout(device: &Device) -> NamedPipelineLayout<Self, Self::PushSlots>;
+}
+
+impl<S: PipelineLayoutSlots> NamedPipelineLayout<S, S::PushSlots>{
+    pub fn new(device: &Device) -> Self{
+        S::create_layout(device)
     }
 }
 
@@ -160,37 +483,123 @@ impl<'l> PipelineLayoutBuilder<'l>{
     }
 
     pub fn build(self, device: &wgpu::Device, label: Option<&str>) -> PipelineLayout{
+        self.try_build(device, label)
+            .unwrap_or_else(|err| panic!("Invalid pipeline layout: {}", err))
+    }
 
-        let mut bind_group_layouts = Vec::with_capacity(self.bind_group_layouts.len());
-        for bind_group_layout_desc in self.bind_group_layouts{
-            bind_group_layouts.push(&bind_group_layout_desc.layout);
-        }
-
-        // Convert the push_const_layouts to push_const_ranges using alignment
-        let mut offset = 0;
-        let push_const_ranges: Vec<wgpu::PushConstantRange> = self.push_const_layouts.iter()
-            .map(|x| {
-                // align to 4 bytes.
-                let size_aligned = (((x.size as i32 - 4)/4 + 1)*4) as u32;
-                let range = Range::<u32>{
-                    start: offset,
-                    end: offset + size_aligned,
-                };
-                offset = range.end;
-                wgpu::PushConstantRange{
-                    stages: x.stages,
-                    range,
-                }
-            }).collect();
-
-        PipelineLayout{
-            layout: device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor{
-                label,
-                bind_group_layouts: &bind_group_layouts,
-                push_constant_ranges: &push_const_ranges,
-            }),
-            push_const_ranges,
-        }
+    pub fn try_build(self, device: &wgpu::Device, label: Option<&str>) -> Result<PipelineLayout>{
+        PipelineLayout::try_from_layouts(device, &self.bind_group_layouts, &self.push_const_layouts, label)
+    }
+}
+
+///
+/// The drawing methods shared by RenderPassPipeline and RenderBundleEncoderPipeline.
+///
+/// Implementors only provide the underlying encoder and the number of indices of the last index
+/// buffer set by set_index_buffer.
+///
+pub trait RenderEncoderPipeline<'a>{
+    type Encoder: wgpu::util::RenderEncoder<'a>;
+
+    fn encoder(&mut self) -> &mut Self::Encoder;
+    fn index_count(&mut self) -> &mut Option<u32>;
+
+    fn set_vertex_buffer<T: VertLayout>(&mut self, index: u32, buffer_slice: BufferSlice<'a, T>){
+        self.encoder().set_vertex_buffer(
+            index,
+            buffer_slice.into()
+        );
+    }
+
+    ///
+    /// Sets the index buffer with the index format derived from its element type.
+    /// The number of indices in the slice is remembered so that draw_indexed can default to the
+    /// full range.
+    ///
+    fn set_index_buffer<I: IndexFormat>(&mut self, buffer_slice: BufferSlice<'a, I>){
+        *self.index_count() = Some(buffer_slice.len() as u32);
+        self.encoder().set_index_buffer(buffer_slice.into(), I::index_format());
+    }
+
+    ///
+    /// Sets the vertex buffer at slot 0 and the index buffer of the mesh.
+    ///
+    fn set_mesh<V: VertLayout, I: IndexFormat>(&mut self, mesh: &'a IndexedMesh<V, I>){
+        self.set_vertex_buffer(0, mesh.vertices.slice(..));
+        self.set_index_buffer(mesh.indices.slice(..));
+    }
+
+    ///
+    /// Sets the buffers of the mesh and draws all of its indices.
+    ///
+    fn draw_mesh<V: VertLayout, I: IndexFormat>(&mut self, mesh: &'a IndexedMesh<V, I>, instances: Range<u32>){
+        self.set_mesh(mesh);
+        self.encoder().draw_indexed(
+            0..mesh.indices.count(),
+            0,
+            instances.start..instances.end
+        );
+    }
+
+    fn draw(&mut self, vertices: Range<u32>, instances: Range<u32>){
+        self.encoder().draw(
+            vertices.start..vertices.end,
+            instances.start..instances.end
+        );
+    }
+
+    ///
+    /// Draws the indices in the range.
+    /// An unbounded range is limited by the number of indices in the last index buffer set by
+    /// set_index_buffer.
+    /// Returns an error if the range is unbounded and no index buffer has been set or if a bound
+    /// of the range overflows.
+    ///
+    /// ```ignore
+    /// rpass_ppl.set_index_buffer(indices.slice(..));
+    /// rpass_ppl.draw_indexed(.., 0, 0..1).unwrap();
+    /// ```
+    ///
+    fn draw_indexed<R: RangeBounds<u32>>(&mut self, indices: R, base_vertex: i32, instances: Range<u32>) -> Result<()>{
+        let start = match indices.start_bound(){
+            Bound::Included(start) => *start,
+            Bound::Excluded(start) => start.checked_add(1)
+                .ok_or_else(|| anyhow::anyhow!("Start of the index range overflows"))?,
+            Bound::Unbounded => 0,
+        };
+        let end = match indices.end_bound(){
+            Bound::Included(end) => end.checked_add(1)
+                .ok_or_else(|| anyhow::anyhow!("End of the index range overflows"))?,
+            Bound::Excluded(end) => *end,
+            Bound::Unbounded => (*self.index_count())
+                .ok_or_else(|| anyhow::anyhow!("draw_indexed with an unbounded range requires an index buffer set by set_index_buffer"))?,
+        };
+        self.encoder().draw_indexed(
+            start..end,
+            base_vertex,
+            instances.start..instances.end
+        );
+        Ok(())
+    }
+
+    ///
+    /// Draw with the arguments at index in the indirect_buffer.
+    ///
+    fn draw_indirect(&mut self, indirect_buffer: &'a Buffer<DrawIndirect>, index: usize){
+        self.encoder().draw_indirect(
+            &indirect_buffer.buffer,
+            indirect_offset(indirect_buffer, index, 1)
+        );
+    }
+
+    ///
+    /// Draw indexed with the arguments at index in the indirect_buffer.
+    ///
+    fn draw_indexed_indirect(&mut self, indirect_buffer: &'a Buffer<DrawIndexedIndirect>, index: usize){
+        self.encoder().draw_indexed_indirect(
+            &indirect_buffer.buffer,
+            indirect_offset(indirect_buffer, index, 1)
+        );
     }
 }
 
@@ -200,6 +609,14 @@ pub struct RenderPassPipeline<'rp, 'rpr>{
 }
 
 impl<'rp, 'rpr> RenderPassPipeline<'rp, 'rpr>{
+    ///
+    /// Sets the bind group at the index of a slot generated by pipeline_layout!.
+    /// The content of the bind group has to match the type of the slot.
+    ///
+    pub fn set_bind_group_slot<B: binding::GetBindGroup + SlotContent>(&mut self, slot: BindGroupSlot<B::Content>, bind_group: &'rp B, offsets: &'rp [wgpu::DynamicOffset]){
+        self.render_pass.render_pass.set_bind_group(slot.index, bind_group.bind_group(), offsets);
+    }
+
     pub fn set_bind_group<B: binding::GetBindGroup>(&mut self, index: u32, bind_group: &'rp B, offsets: &'rp [wgpu::DynamicOffset]){
         self.render_pass.render_pass.set_bind_group(
             index,
@@ -212,46 +629,103 @@ impl<'rp, 'rpr> RenderPassPipeline<'rp, 'rpr>{
         for (i, bind_group) in bind_groups.iter().enumerate(){
             self.render_pass.render_pass.set_bind_group(
                 i as u32,
-                &bind_group,
+                bind_group,
                 &[]
             );
         }
     }
 
-    pub fn set_push_const<C: PushConstant>(&mut self, index: usize, constant: &C){
+    ///
+    /// Sets the push constant at index.
+    /// Returns an error if C is not the type the pipeline layout was created with.
+    ///
+    pub fn set_push_const<C: PushConstant>(&mut self, index: usize, constant: &C) -> Result<()>{
+        let range = push_const_range::<C>(&self.pipeline.push_const_ranges, index)?;
+        self.render_pass.render_pass.set_push_constants(
+            range.stages, 
+            range.range.start,
+            &push_const_bytes(constant));
+        Ok(())
+    }
+
+    ///
+    /// Sets the push constant of a slot whose type has already been checked.
+    ///
+    pub fn set_push_const_slot<C: PushConstant>(&mut self, slot: PushConstantSlot<C>, constant: &C){
+        let range = slot.range(&self.pipeline.push_const_ranges);
         self.render_pass.render_pass.set_push_constants(
-            self.pipeline.push_const_ranges[index].stages, 
-            self.pipeline.push_const_ranges[index].range.start,
-            bytemuck::bytes_of(constant));
+            range.stages, 
+            range.range.start,
+            &push_const_bytes(constant));
     }
 
-    pub fn set_vertex_buffer<T: VertLayout>(&mut self, index: u32, buffer_slice: BufferSlice<'rp, T>){
-        self.render_pass.render_pass.set_vertex_buffer(
-            index,
-            buffer_slice.into()
-        );
+    ///
+    /// Sets all push constants stored in the PushConstantVec.
+    ///
+    pub fn set_push_consts(&mut self, constants: &PushConstantVec){
+        for (i, range) in constants.ranges.iter().enumerate(){
+            self.render_pass.render_pass.set_push_constants(
+                range.stages,
+                range.range.start,
+                constants.range_bytes(i));
+        }
     }
 
-    pub fn set_index_buffer(&mut self, buffer_slice: BufferSlice<'rp, u32>){
-        self.render_pass.render_pass.set_index_buffer(buffer_slice.into(), wgpu::IndexFormat::Uint32);
+    ///
+    /// Issue count draws with the arguments starting at index in the indirect_buffer.
+    ///
+    /// Requires wgpu::Features::MULTI_DRAW_INDIRECT.
+    ///
+    pub fn multi_draw_indirect(&mut self, indirect_buffer: &'rp Buffer<DrawIndirect>, index: usize, count: u32){
+        self.render_pass.render_pass.multi_draw_indirect(
+            &indirect_buffer.buffer,
+            indirect_offset(indirect_buffer, index, count as usize),
+            count
+        );
     }
 
-    pub fn set_index_buffer16(&mut self, buffer_slice: BufferSlice<'rp, u16>){
-        self.render_pass.render_pass.set_index_buffer(buffer_slice.into(), wgpu::IndexFormat::Uint16);
+    ///
+    /// Issue count indexed draws with the arguments starting at index in the indirect_buffer.
+    ///
+    /// Requires wgpu::Features::MULTI_DRAW_INDIRECT.
+    ///
+    pub fn multi_draw_indexed_indirect(&mut self, indirect_buffer: &'rp Buffer<DrawIndexedIndirect>, index: usize, count: u32){
+        self.render_pass.render_pass.multi_draw_indexed_indirect(
+            &indirect_buffer.buffer,
+            indirect_offset(indirect_buffer, index, count as usize),
+            count
+        );
     }
 
-    pub fn draw(&mut self, vertices: Range<u32>, instances: Range<u32>){
-        self.render_pass.render_pass.draw(
-            vertices.start..vertices.end,
-            instances.start..instances.end
+    ///
+    /// Issue up to max_count draws with the arguments starting at index in the indirect_buffer.
+    /// The number of draws is read from count_buffer at count_index.
+    ///
+    /// Requires wgpu::Features::MULTI_DRAW_INDIRECT_COUNT.
+    ///
+    pub fn multi_draw_indirect_count(&mut self, indirect_buffer: &'rp Buffer<DrawIndirect>, index: usize, count_buffer: &'rp Buffer<u32>, count_index: usize, max_count: u32){
+        self.render_pass.render_pass.multi_draw_indirect_count(
+            &indirect_buffer.buffer,
+            indirect_offset(indirect_buffer, index, max_count as usize),
+            &count_buffer.buffer,
+            indirect_offset(count_buffer, count_index, 1),
+            max_count
         );
     }
 
-    pub fn draw_indexed(&mut self, indices: Range<u32>, base_vertex: i32, instances: Range<u32>){
-        self.render_pass.render_pass.draw_indexed(
-            indices.start..indices.end, 
-            base_vertex, 
-            instances.start..instances.end
+    ///
+    /// Issue up to max_count indexed draws with the arguments starting at index in the indirect_buffer.
+    /// The number of draws is read from count_buffer at count_index.
+    ///
+    /// Requires wgpu::Features::MULTI_DRAW_INDIRECT_COUNT.
+    ///
+    pub fn multi_draw_indexed_indirect_count(&mut self, indirect_buffer: &'rp Buffer<DrawIndexedIndirect>, index: usize, count_buffer: &'rp Buffer<u32>, count_index: usize, max_count: u32){
+        self.render_pass.render_pass.multi_draw_indexed_indirect_count(
+            &indirect_buffer.buffer,
+            indirect_offset(indirect_buffer, index, max_count as usize),
+            &count_buffer.buffer,
+            indirect_offset(count_buffer, count_index, 1),
+            max_count
         );
     }
 
@@ -263,6 +737,73 @@ impl<'rp, 'rpr> RenderPassPipeline<'rp, 'rpr>{
         );
     }
 
+    ///
+    /// Sets the scissor rectangle in pixels.
+    /// Fragments outside of it are discarded.
+    ///
+    pub fn set_scissor_rect(&mut self, x: Range<u32>, y: Range<u32>){
+        assert_le!(x.start, x.end, "scissor rect has negative width");
+        assert_le!(y.start, y.end, "scissor rect has negative height");
+        self.render_pass.render_pass.set_scissor_rect(
+            x.start, y.start,
+            x.end - x.start, y.end - y.start
+        );
+    }
+
+    ///
+    /// Sets the blend constant used by the BlendFactor::Constant and BlendFactor::OneMinusConstant
+    /// blend factors.
+    /// Returns an error if the pipeline does not blend with a constant.
+    ///
+    pub fn set_blend_constant(&mut self, color: wgpu::Color) -> Result<()>{
+        if !self.pipeline.uses_blend_constant(){
+            return Err(anyhow::anyhow!("Setting a blend constant for a pipeline that does not blend with a constant"));
+        }
+        self.render_pass.render_pass.set_blend_constant(color);
+        Ok(())
+    }
+
+    ///
+    /// Sets the stencil reference used by the stencil test.
+    /// Returns an error if the stencil state of the pipeline does not read it.
+    ///
+    pub fn set_stencil_reference(&mut self, reference: u32) -> Result<()>{
+        if !self.pipeline.uses_stencil_reference(){
+            return Err(anyhow::anyhow!("Setting a stencil reference for a pipeline whose stencil state does not read it"));
+        }
+        self.render_pass.render_pass.set_stencil_reference(reference);
+        Ok(())
+    }
+
+    ///
+    /// Starts a debug group that is shown in graphics debuggers.
+    /// Has to be closed by pop_debug_group before the RenderPass ends, see RenderPass::end.
+    ///
+    pub fn push_debug_group(&mut self, label: &str){
+        self.render_pass.debug_group_depth += 1;
+        self.render_pass.render_pass.push_debug_group(label);
+    }
+
+    ///
+    /// Ends the debug group opened by the last push_debug_group.
+    /// Returns an error if there is no open debug group.
+    ///
+    pub fn pop_debug_group(&mut self) -> Result<()>{
+        if self.render_pass.debug_group_depth == 0{
+            return Err(anyhow::anyhow!("pop_debug_group without matching push_debug_group"));
+        }
+        self.render_pass.debug_group_depth -= 1;
+        self.render_pass.render_pass.pop_debug_group();
+        Ok(())
+    }
+
+    ///
+    /// Inserts a single debug marker that is shown in graphics debuggers.
+    ///
+    pub fn insert_debug_marker(&mut self, label: &str){
+        self.render_pass.render_pass.insert_debug_marker(label);
+    }
+
     pub fn set_pipeline(&'rpr mut self, pipeline: &'rp RenderPipeline) -> Self{
         self.render_pass.render_pass.set_pipeline(&pipeline.pipeline);
         Self{
@@ -272,12 +813,100 @@ impl<'rp, 'rpr> RenderPassPipeline<'rp, 'rpr>{
     }
 }
 
+impl<'rp, 'rpr> RenderEncoderPipeline<'rp> for RenderPassPipeline<'rp, 'rpr>{
+    type Encoder = wgpu::RenderPass<'rp>;
+
+    #[inline]
+    fn encoder(&mut self) -> &mut Self::Encoder{
+        &mut self.render_pass.render_pass
+    }
+
+    #[inline]
+    fn index_count(&mut self) -> &mut Option<u32>{
+        &mut self.render_pass.index_count
+    }
+}
+
+///
+/// The arguments of an indirect draw call as they are read from an INDIRECT buffer.
+///
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct DrawIndirect{
+    pub vertex_count: u32,
+    pub instance_count: u32,
+    pub base_vertex: u32,
+    pub base_instance: u32,
+}
+
+impl DrawIndirect{
+    pub fn new(vertices: Range<u32>, instances: Range<u32>) -> Self{
+        Self{
+            vertex_count: vertices.end - vertices.start,
+            instance_count: instances.end - instances.start,
+            base_vertex: vertices.start,
+            base_instance: instances.start,
+        }
+    }
+}
+
+///
+/// The arguments of an indirect indexed draw call as they are read from an INDIRECT buffer.
+///
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct DrawIndexedIndirect{
+    pub index_count: u32,
+    pub instance_count: u32,
+    pub base_index: u32,
+    pub vertex_offset: i32,
+    pub base_instance: u32,
+}
+
+impl DrawIndexedIndirect{
+    pub fn new(indices: Range<u32>, base_vertex: i32, instances: Range<u32>) -> Self{
+        Self{
+            index_count: indices.end - indices.start,
+            instance_count: instances.end - instances.start,
+            base_index: indices.start,
+            vertex_offset: base_vertex,
+            base_instance: instances.start,
+        }
+    }
+}
+
+///
+/// The workgroup counts of an indirect dispatch as they are read from an INDIRECT buffer.
+///
 #[repr(C)]
-#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct DispatchIndirect{
-    x: u32,
-    y: u32,
-    z: u32,
+    pub x: u32,
+    pub y: u32,
+    pub z: u32,
+}
+
+impl DispatchIndirect{
+    pub fn new(x: u32, y: u32, z: u32) -> Self{
+        Self{x, y, z}
+    }
+}
+
+///
+/// Returns the byte offset of the element at index in an indirect buffer from which count
+/// elements are read.
+/// Panics if the elements are out of bounds, a count of zero is valid at index == len.
+///
+#[inline]
+pub(crate) fn indirect_offset<C: bytemuck::Pod>(buffer: &Buffer<C>, index: usize, count: usize) -> wgpu::BufferAddress{
+    assert!(indirect_range_in_bounds(buffer.len(), index, count),
+        "indirect range of {} elements at index {} out of bounds of {} elements", count, index, buffer.len());
+    (index * std::mem::size_of::<C>()) as wgpu::BufferAddress
+}
+
+#[inline]
+fn indirect_range_in_bounds(len: usize, index: usize, count: usize) -> bool{
+    index.checked_add(count).is_some_and(|end| end <= len)
 }
 
 ///
@@ -285,7 +914,9 @@ pub struct DispatchIndirect{
 ///
 #[derive(DerefMut)]
 pub struct ComputePass<'cp>{
+    #[target]
     pub cpass: wgpu::ComputePass<'cp>,
+    debug_group_depth: u32,
 }
 
 impl<'cp> ComputePass<'cp>{
@@ -295,9 +926,30 @@ impl<'cp> ComputePass<'cp>{
         });
         Self{
             cpass,
+            debug_group_depth: 0,
+        }
+    }
+
+    ///
+    /// Ends the ComputePass.
+    /// Returns an error if debug groups have been left open, they are closed before the pass ends.
+    ///
+    pub fn end(mut self) -> Result<()>{
+        match self.close_debug_groups(){
+            0 => Ok(()),
+            depth => Err(anyhow::anyhow!("ComputePass ended with {} open debug groups", depth)),
         }
     }
 
+    fn close_debug_groups(&mut self) -> u32{
+        let depth = self.debug_group_depth;
+        for _ in 0..depth{
+            self.cpass.pop_debug_group();
+        }
+        self.debug_group_depth = 0;
+        depth
+    }
+
     pub fn set_pipeline(&mut self, pipeline: &'cp ComputePipeline) -> ComputePassPipeline<'cp, '_>{
         self.cpass.set_pipeline(&pipeline.pipeline);
         ComputePassPipeline{
@@ -308,6 +960,19 @@ impl<'cp> ComputePass<'cp>{
 
 }
 
+impl<'cp> Drop for ComputePass<'cp>{
+    ///
+    /// Closes the debug groups that have been left open so that they do not reach wgpu as a
+    /// validation error and logs an error. Use ComputePass::end to handle it instead.
+    ///
+    fn drop(&mut self){
+        let depth = self.close_debug_groups();
+        if depth > 0{
+            log::error!("ComputePass dropped with {} open debug groups", depth);
+        }
+    }
+}
+
 ///
 /// A ComputePass with pipeline needed for push_const offsets.
 ///
@@ -317,22 +982,125 @@ pub struct ComputePassPipeline<'cp, 'cpr>{
 }
 
 impl<'cp, 'cpr> ComputePassPipeline<'cp, 'cpr>{
+    ///
+    /// Sets the bind group at the index of a slot generated by pipeline_layout!.
+    /// The content of the bind group has to match the type of the slot.
+    ///
+    pub fn set_bind_group_slot<B: binding::GetBindGroup + SlotContent>(&mut self, slot: BindGroupSlot<B::Content>, bind_group: &'cp B, offsets: &'cp [wgpu::DynamicOffset]){
+        self.cpass.cpass.set_bind_group(slot.index, bind_group.bind_group(), offsets);
+    }
+
     pub fn set_bind_group<B: binding::GetBindGroup>(&mut self, index: u32, bind_group: &'cp B, offsets: &'cp [wgpu::DynamicOffset]){
         self.cpass.cpass.set_bind_group(index, bind_group.bind_group(), offsets);
     }
 
-    pub fn set_push_const<C: PushConstant>(&mut self, index: u32, constant: &C){
+    ///
+    /// Sets the push constant at index.
+    /// Returns an error if C is not the type the pipeline layout was created with.
+    ///
+    pub fn set_push_const<C: PushConstant>(&mut self, index: usize, constant: &C) -> Result<()>{
+        let range = push_const_range::<C>(&self.pipeline.push_const_ranges, index)?;
+        self.cpass.cpass.set_push_constants(
+            range.range.start,
+            &push_const_bytes(constant));
+        Ok(())
+    }
+
+    ///
+    /// Sets the push constant of a slot whose type has already been checked.
+    ///
+    pub fn set_push_const_slot<C: PushConstant>(&mut self, slot: PushConstantSlot<C>, constant: &C){
         self.cpass.cpass.set_push_constants(
-            self.pipeline.push_const_ranges[index as usize].range.start,
-            bytemuck::bytes_of(constant));
+            slot.range(&self.pipeline.push_const_ranges).range.start,
+            &push_const_bytes(constant));
+    }
+
+    ///
+    /// Sets all push constants stored in the PushConstantVec.
+    ///
+    pub fn set_push_consts(&mut self, constants: &PushConstantVec){
+        for (i, range) in constants.ranges.iter().enumerate(){
+            self.cpass.cpass.set_push_constants(
+                range.range.start,
+                constants.range_bytes(i));
+        }
     }
 
     pub fn dispatch(&mut self, x: u32, y: u32, z: u32){
         self.cpass.cpass.dispatch(x, y, z);
     }
 
-    pub fn dispatch_indirect(&mut self, indirect_buffer: &'cp Buffer<DispatchIndirect>, indirect_offset: wgpu::BufferAddress){
-        self.cpass.cpass.dispatch_indirect(&indirect_buffer.buffer, indirect_offset);
+    ///
+    /// Dispatches enough workgroups of the pipeline's workgroup size to cover n_items
+    /// invocations along x.
+    ///
+    pub fn dispatch_for(&mut self, n_items: u32) -> Result<()>{
+        let [x, y, z] = self.pipeline.workgroup_count([n_items, 1, 1])?;
+        self.dispatch(x, y, z);
+        Ok(())
+    }
+
+    ///
+    /// Dispatches enough workgroups of the pipeline's workgroup size to cover a grid of
+    /// width x height invocations.
+    ///
+    pub fn dispatch_2d(&mut self, width: u32, height: u32) -> Result<()>{
+        let [x, y, z] = self.pipeline.workgroup_count([width, height, 1])?;
+        self.dispatch(x, y, z);
+        Ok(())
+    }
+
+    ///
+    /// Dispatches enough workgroups of the pipeline's workgroup size to cover the extent.
+    ///
+    pub fn dispatch_extent(&mut self, extent: wgpu::Extent3d) -> Result<()>{
+        let [x, y, z] = self.pipeline.workgroup_count([extent.width, extent.height, extent.depth_or_array_layers])?;
+        self.dispatch(x, y, z);
+        Ok(())
+    }
+
+    ///
+    /// Dispatches enough workgroups of the pipeline's workgroup size to cover every texel of the
+    /// storage texture.
+    ///
+    pub fn dispatch_texture<F: format::StorageFormat, A: access::StorageAccess, D: dim::ViewDimension>(&mut self, texture: &StorageTexture<F, A, D>) -> Result<()>{
+        self.dispatch_extent(texture.size)
+    }
+
+    ///
+    /// Dispatch with the workgroup counts at index in the indirect_buffer.
+    ///
+    pub fn dispatch_indirect(&mut self, indirect_buffer: &'cp Buffer<DispatchIndirect>, index: usize){
+        self.cpass.cpass.dispatch_indirect(&indirect_buffer.buffer, indirect_offset(indirect_buffer, index, 1));
+    }
+
+    ///
+    /// Starts a debug group that is shown in graphics debuggers.
+    /// Has to be closed by pop_debug_group before the ComputePass ends, see ComputePass::end.
+    ///
+    pub fn push_debug_group(&mut self, label: &str){
+        self.cpass.debug_group_depth += 1;
+        self.cpass.cpass.push_debug_group(label);
+    }
+
+    ///
+    /// Ends the debug group opened by the last push_debug_group.
+    /// Returns an error if there is no open debug group.
+    ///
+    pub fn pop_debug_group(&mut self) -> Result<()>{
+        if self.cpass.debug_group_depth == 0{
+            return Err(anyhow::anyhow!("pop_debug_group without matching push_debug_group"));
+        }
+        self.cpass.debug_group_depth -= 1;
+        self.cpass.cpass.pop_debug_group();
+        Ok(())
+    }
+
+    ///
+    /// Inserts a single debug marker that is shown in graphics debuggers.
+    ///
+    pub fn insert_debug_marker(&mut self, label: &str){
+        self.cpass.cpass.insert_debug_marker(label);
     }
 
     pub fn set_pipeline(&'cpr mut self, pipeline: &'cp ComputePipeline) -> Self{
@@ -351,7 +1119,41 @@ impl<'cp, 'cpr> ComputePassPipeline<'cp, 'cpr>{
 pub struct ComputePipeline{
     #[target]
     pub pipeline: wgpu::ComputePipeline,
-    pub push_const_ranges: Vec<wgpu::PushConstantRange>,
+    pub push_const_ranges: Vec<PushConstantRange>,
+    /// The local size of the entry point used by dispatch_for and dispatch_2d or None if it
+    /// could not be reflected from the shader and has not been set.
+    pub workgroup_size: Option<[u32; 3]>,
+    max_workgroups_per_dimension: u32,
+}
+
+impl ComputePipeline{
+    ///
+    /// Returns a typed slot for the push constant at index if it holds a C.
+    ///
+    pub fn push_const_slot<C: PushConstant>(&self, index: usize) -> Result<PushConstantSlot<C>>{
+        PushConstantSlot::new(&self.push_const_ranges, index)
+    }
+
+    ///
+    /// Returns the number of workgroups needed to cover the items in every dimension.
+    /// Returns an error if the workgroup size is unknown or a count exceeds
+    /// max_compute_workgroups_per_dimension.
+    ///
+    pub fn workgroup_count(&self, items: [u32; 3]) -> Result<[u32; 3]>{
+        let workgroup_size = self.workgroup_size
+            .ok_or_else(|| anyhow::anyhow!("The workgroup size could not be reflected from the shader, set it with ComputePipelineBuilder::set_workgroup_size"))?;
+        let mut count = [0; 3];
+        for i in 0..3{
+            count[i] = items[i].div_ceil(workgroup_size[i]);
+            if count[i] > self.max_workgroups_per_dimension{
+                return Err(anyhow::anyhow!(
+                    "Dispatching {} items with workgroup size {} needs {} workgroups but the device only allows {}",
+                    items[i], workgroup_size[i], count[i], self.max_workgroups_per_dimension
+                ));
+            }
+        }
+        Ok(count)
+    }
 }
 
 ///
@@ -361,8 +1163,9 @@ pub struct ComputePipeline{
 pub struct ComputePipelineBuilder<'cpb>{
     label: wgpu::Label<'cpb>,
     layout: Option<&'cpb PipelineLayout>,
-    module: &'cpb wgpu::ShaderModule,
+    module: &'cpb ShaderModule,
     entry_point: &'cpb str,
+    workgroup_size: Option<[u32; 3]>,
 }
 
 impl<'cpb> ComputePipelineBuilder<'cpb>{
@@ -373,9 +1176,19 @@ impl<'cpb> ComputePipelineBuilder<'cpb>{
             layout: None,
             module,
             entry_point: "main",
+            workgroup_size: None,
         }
     }
 
+    ///
+    /// Overrides the workgroup size reflected from the shader.
+    /// Every dimension has to be greater than zero, otherwise building the pipeline fails.
+    ///
+    pub fn set_workgroup_size(mut self, workgroup_size: [u32; 3]) -> Self{
+        self.workgroup_size = Some(workgroup_size);
+        self
+    }
+
     pub fn set_entry_point(mut self, entry_point: &'cpb str) -> Self{
         self.entry_point = entry_point;
         self
@@ -391,9 +1204,37 @@ impl<'cpb> ComputePipelineBuilder<'cpb>{
         self
     }
 
+    pub(crate) fn cache_key(&self) -> pipeline_cache::ComputePipelineKey{
+        pipeline_cache::ComputePipelineKey{
+            layout: self.layout.map(|layout| layout.cache_key()),
+            shader: self.module.cache_key(),
+            entry_point: self.entry_point.to_string(),
+            workgroup_size: self.workgroup_size,
+        }
+    }
+
     pub fn build(&mut self, device: &wgpu::Device) -> ComputePipeline{
-        let layout = self.layout.expect("no layout provided");
-        ComputePipeline{
+        self.try_build(device)
+            .unwrap_or_else(|err| panic!("Invalid compute pipeline: {}", err))
+    }
+
+    ///
+    /// Builds the pipeline.
+    /// The workgroup size is reflected from the shader if it has not been set. If the shader
+    /// could not be reflected it is unknown and ComputePipeline::workgroup_count returns an error.
+    ///
+    /// Returns an error if no layout has been set or a dimension of the workgroup size is zero.
+    ///
+    pub fn try_build(&mut self, device: &wgpu::Device) -> Result<ComputePipeline>{
+        let layout = self.layout.ok_or_else(|| anyhow::anyhow!("No layout provided"))?;
+        let workgroup_size = self.workgroup_size
+            .or_else(|| self.module.interface.as_ref().map(|interface| interface.workgroup_size));
+        if let Some(workgroup_size) = workgroup_size{
+            if workgroup_size.contains(&0){
+                return Err(anyhow::anyhow!("Workgroup size {:?} has a zero dimension", workgroup_size));
+            }
+        }
+        Ok(ComputePipeline{
             pipeline: device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor{
                 label: self.label,
                 layout: Some(&layout.layout),
@@ -401,7 +1242,9 @@ impl<'cpb> ComputePipelineBuilder<'cpb>{
                 entry_point: self.entry_point,
             }),
             push_const_ranges: layout.push_const_ranges.clone(),
-        }
+            workgroup_size,
+            max_workgroups_per_dimension: device.limits().max_compute_workgroups_per_dimension,
+        })
     }
 }
 
@@ -411,7 +1254,10 @@ impl<'cpb> ComputePipelineBuilder<'cpb>{
 ///
 #[derive(DerefMut)]
 pub struct RenderPass<'rp>{
+    #[target]
     pub render_pass: wgpu::RenderPass<'rp>,
+    index_count: Option<u32>,
+    debug_group_depth: u32,
 }
 
 impl<'rp> RenderPass<'rp>{
@@ -424,6 +1270,35 @@ impl<'rp> RenderPass<'rp>{
         }
     }
 
+    ///
+    /// Executes the pre-recorded RenderBundles.
+    /// This resets the pipeline, bind groups and buffers set in this RenderPass.
+    ///
+    pub fn execute_bundles<I: IntoIterator<Item = &'rp wgpu::RenderBundle>>(&mut self, render_bundles: I){
+        self.index_count = None;
+        self.render_pass.execute_bundles(render_bundles.into_iter());
+    }
+
+    ///
+    /// Ends the RenderPass.
+    /// Returns an error if debug groups have been left open, they are closed before the pass ends.
+    ///
+    pub fn end(mut self) -> Result<()>{
+        match self.close_debug_groups(){
+            0 => Ok(()),
+            depth => Err(anyhow::anyhow!("RenderPass ended with {} open debug groups", depth)),
+        }
+    }
+
+    fn close_debug_groups(&mut self) -> u32{
+        let depth = self.debug_group_depth;
+        for _ in 0..depth{
+            self.render_pass.pop_debug_group();
+        }
+        self.debug_group_depth = 0;
+        depth
+    }
+
     /* TODO: maybe remove RenderPassPipeline
        #[inline]
        pub fn set_bind_group(&mut self, index: u32, bind_group: &'rp wgpu::BindGroup, offsets: &'rp [wgpu::DynamicOffset]){
@@ -432,18 +1307,33 @@ impl<'rp> RenderPass<'rp>{
        */
 }
 
+impl<'rp> Drop for RenderPass<'rp>{
+    ///
+    /// Closes the debug groups that have been left open so that they do not reach wgpu as a
+    /// validation error and logs an error. Use RenderPass::end to handle it instead.
+    ///
+    fn drop(&mut self){
+        let depth = self.close_debug_groups();
+        if depth > 0{
+            log::error!("RenderPass dropped with {} open debug groups", depth);
+        }
+    }
+}
+
 ///
 /// A builder for the RenderPass.
 ///
 #[derive(Default)]
 pub struct RenderPassBuilder<'rp>{
     color_attachments: Vec<wgpu::RenderPassColorAttachment<'rp>>,
+    depth_stencil_attachment: Option<wgpu::RenderPassDepthStencilAttachment<'rp>>,
 }
 
 impl<'rp> RenderPassBuilder<'rp>{
     pub fn new() -> Self{
         Self{
             color_attachments: Vec::new(),
+            depth_stencil_attachment: None,
         }
     }
 
@@ -452,14 +1342,25 @@ impl<'rp> RenderPassBuilder<'rp>{
         self
     }
 
-    // TODO: add depth_stencil_attachment
+    ///
+    /// Sets the depth stencil attachment.
+    /// A pass without color attachments only writes to the depth stencil attachment, which is
+    /// used with pipelines created by RenderPipelineBuilder::vertex_only.
+    ///
+    pub fn set_depth_stencil_attachment(mut self, depth_stencil_attachment: wgpu::RenderPassDepthStencilAttachment<'rp>) -> Self{
+        self.depth_stencil_attachment = Some(depth_stencil_attachment);
+        self
+    }
+
     pub fn begin(self, encoder: &'rp mut wgpu::CommandEncoder, label: Option<&'rp str>) -> RenderPass<'rp>{
         RenderPass{
             render_pass: encoder.begin_render_pass(&wgpu::RenderPassDescriptor{
                 label,
                 color_attachments: &self.color_attachments,
-                depth_stencil_attachment: None,
+                depth_stencil_attachment: self.depth_stencil_attachment,
             }),
+            index_count: None,
+            debug_group_depth: 0,
         }
     }
 }
@@ -468,12 +1369,14 @@ impl<'rp> RenderPassBuilder<'rp>{
 /// A Builder for a RenderPipeline.
 ///
 /// Pipeline layout has to be set.
+/// Pipelines created with [`RenderPipelineBuilder::vertex_only`] have no fragment stage and
+/// require a depth stencil state.
 ///
 pub struct RenderPipelineBuilder<'rpb>{
     label: Option<&'rpb str>,
     layout: Option<&'rpb PipelineLayout>,
     vertex: VertexState<'rpb>,
-    fragment: FragmentState<'rpb>,
+    fragment: Option<FragmentState<'rpb>>,
     primitive: wgpu::PrimitiveState,
     depth_stencil: Option<wgpu::DepthStencilState>,
     multisample: wgpu::MultisampleState,
@@ -482,7 +1385,35 @@ pub struct RenderPipelineBuilder<'rpb>{
 
 impl<'rpb> RenderPipelineBuilder<'rpb>{
 
+    ///
+    /// Creates a builder with the vertex and fragment stage of the program.
+    /// A program without a fragment stage results in a vertex only builder.
+    /// Returns an error if the program has no vertex stage.
+    ///
+    pub fn from_program(program: &'rpb ShaderProgram) -> Result<Self>{
+        let vertex_shader = program.vertex.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("ShaderProgram has no vertex stage"))?;
+        Ok(match program.fragment{
+            Some(ref fragment_shader) => Self::new(vertex_shader, fragment_shader),
+            None => Self::vertex_only(vertex_shader),
+        })
+    }
+
     pub fn new(vertex_shader: &'rpb VertexShader, fragment_shader: &'rpb FragmentShader) -> Self{
+        Self::with_fragment(vertex_shader, Some(FragmentState::new(fragment_shader)))
+    }
+
+    ///
+    /// Creates a builder for a pipeline without a fragment stage.
+    ///
+    /// Such a pipeline only writes to the depth stencil attachment, for example when rendering
+    /// shadow maps or a depth pre-pass. A depth stencil state has to be set before building.
+    ///
+    pub fn vertex_only(vertex_shader: &'rpb VertexShader) -> Self{
+        Self::with_fragment(vertex_shader, None)
+    }
+
+    fn with_fragment(vertex_shader: &'rpb VertexShader, fragment: Option<FragmentState<'rpb>>) -> Self{
         let label = None;
         let layout = None;
         let primitive = wgpu::PrimitiveState{
@@ -507,12 +1438,6 @@ impl<'rpb> RenderPipelineBuilder<'rpb>{
             entry_point: DEFAULT_ENTRY_POINT,
             shader: vertex_shader,
         };
-        let fragment = FragmentState{
-            targets: Vec::new(),
-            entry_point: DEFAULT_ENTRY_POINT,
-            shader: fragment_shader,
-        };
-
         Self{
             label,
             layout,
@@ -620,9 +1545,11 @@ impl<'rpb> RenderPipelineBuilder<'rpb>{
     ///
     /// Has to be pushed in the same order as their corresponding color attachements.
     ///
+    /// Panics if the pipeline has no fragment stage.
+    ///
     #[inline]
     pub fn push_target_replace(mut self, format: wgpu::TextureFormat) -> Self{
-        self.fragment = self.fragment.push_target_replace(format);
+        self.fragment = Some(self.fragment_state().push_target_replace(format));
         self
     }
 
@@ -631,12 +1558,32 @@ impl<'rpb> RenderPipelineBuilder<'rpb>{
     ///
     /// Has to be pushed in the same order as their corresponding color attachements.
     ///
+    /// Panics if the pipeline has no fragment stage.
+    ///
     #[inline]
     pub fn push_target(mut self, color_target_state: wgpu::ColorTargetState) -> Self{
-        self.fragment.targets.push(color_target_state);
+        self.fragment = Some(self.fragment_state().push_target(color_target_state));
+        self
+    }
+
+    ///
+    /// Pushes a ColorTarget to the fragment state.
+    ///
+    /// Has to be pushed in the same order as their corresponding color attachements.
+    ///
+    /// Panics if the pipeline has no fragment stage or the target is invalid.
+    ///
+    #[inline]
+    pub fn push_color_target(mut self, color_target: ColorTarget) -> Self{
+        self.fragment = Some(self.fragment_state().push_color_target(color_target));
         self
     }
 
+    fn fragment_state(&mut self) -> FragmentState<'rpb>{
+        self.fragment.take()
+            .expect("Vertex only render pipelines have no color targets")
+    }
+
     #[inline]
     pub fn set_layout(mut self, layout: &'rpb PipelineLayout) -> Self{
         self.layout = Some(layout);
@@ -655,29 +1602,64 @@ impl<'rpb> RenderPipelineBuilder<'rpb>{
         self
     }
 
+    ///
+    /// Panics if the pipeline has no fragment stage.
+    ///
     #[inline]
     pub fn frag_entry_point(mut self, entry_point: &'rpb str) -> Self{
-        self.fragment.entry_point = entry_point;
+        self.fragment = Some(self.fragment_state().set_entry_point(entry_point));
         self
     }
 
+    pub(crate) fn cache_key(&self) -> pipeline_cache::RenderPipelineKey{
+        pipeline_cache::RenderPipelineKey{
+            layout: self.layout.map(|layout| layout.cache_key()),
+            vertex_shader: self.vertex.shader.cache_key(),
+            vertex_entry_point: self.vertex.entry_point.to_string(),
+            vertex_buffer_layouts: self.vertex.vertex_buffer_layouts.iter().map(Into::into).collect(),
+            fragment: self.fragment.as_ref().map(|fragment| pipeline_cache::FragmentKey{
+                shader: fragment.shader.cache_key(),
+                entry_point: fragment.entry_point.to_string(),
+                targets: fragment.targets.clone(),
+            }),
+            primitive: self.primitive,
+            depth_stencil: self.depth_stencil.as_ref().map(Into::into),
+            multisample: self.multisample,
+            multiview: self.multiview,
+        }
+    }
+
     pub fn build(self, device: &wgpu::Device) -> RenderPipeline{
+        self.try_build(device)
+            .unwrap_or_else(|err| panic!("Invalid render pipeline: {}", err))
+    }
+
+    ///
+    /// Builds the pipeline.
+    /// Returns an error if the pipeline has no fragment stage and no depth stencil state or
+    /// a color target is invalid.
+    ///
+    pub fn try_build(self, device: &wgpu::Device) -> Result<RenderPipeline>{
+        match self.fragment{
+            Some(ref fragment) => fragment.validate()?,
+            None if self.depth_stencil.is_none() => {
+                return Err(anyhow::anyhow!("A render pipeline without fragment stage requires a depth stencil state"));
+            }
+            None => (),
+        }
 
         let push_const_ranges = match self.layout{
             Some(layout) => layout.push_const_ranges.clone(),
             None => Vec::new(),
         };
 
-        let layout = match self.layout{
-            Some(layout) => Some(&layout.layout),
-            None => None,
-        };
+        let layout = self.layout.map(|layout| &layout.layout);
 
-        let fragment = wgpu::FragmentState{
-            module: self.fragment.shader,
-            entry_point: self.fragment.entry_point,
-            targets: &self.fragment.targets,
-        };
+        let fragment = self.fragment.as_ref().map(|fragment| wgpu::FragmentState{
+            module: fragment.shader,
+            entry_point: fragment.entry_point,
+            targets: &fragment.targets,
+        });
 
         let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor{
             label: self.label,
@@ -687,17 +1669,19 @@ impl<'rpb> RenderPipelineBuilder<'rpb>{
                 entry_point: self.vertex.entry_point,
                 buffers: &self.vertex.vertex_buffer_layouts,
             },
-            fragment: Some(fragment),
+            fragment,
             primitive: self.primitive,
-            depth_stencil: self.depth_stencil,
+            depth_stencil: self.depth_stencil.clone(),
             multisample: self.multisample,
             multiview: self.multiview,
         });
 
-        RenderPipeline{
+        Ok(RenderPipeline{
             pipeline: render_pipeline,
             push_const_ranges,
-        }
+            depth_stencil: self.depth_stencil,
+            targets: self.fragment.map(|fragment| fragment.targets).unwrap_or_default(),
+        })
     }
 }
 
@@ -706,3 +1690,34 @@ impl<'rpb> RenderPipelineBuilder<'rpb>{
 
 // TODO:
 // Counting RenderPass
+
+#[cfg(test)]
+mod test{
+    use super::*;
+
+    #[test]
+    fn color_target_blendable_formats(){
+        assert!(ColorTarget::alpha_blending(wgpu::TextureFormat::Rgba8Unorm).try_build().is_ok());
+        assert!(ColorTarget::additive(wgpu::TextureFormat::Rgba16Float).try_build().is_ok());
+        assert!(ColorTarget::max(wgpu::TextureFormat::R32Float).try_build().is_err());
+        assert!(ColorTarget::multiply(wgpu::TextureFormat::Rgba8Uint).try_build().is_err());
+        assert!(ColorTarget::new(wgpu::TextureFormat::R32Float).try_build().is_ok());
+        assert!(ColorTarget::new(wgpu::TextureFormat::Depth32Float).try_build().is_err());
+
+        let target = ColorTarget::premultiplied_alpha(wgpu::TextureFormat::Bgra8Unorm)
+            .set_write_mask(wgpu::ColorWrites::RED | wgpu::ColorWrites::ALPHA)
+            .build();
+        assert_eq!(target.write_mask, wgpu::ColorWrites::RED | wgpu::ColorWrites::ALPHA);
+        assert_eq!(target.blend, Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING));
+    }
+
+    #[test]
+    fn indirect_range_bounds(){
+        assert!(indirect_range_in_bounds(4, 3, 1));
+        assert!(indirect_range_in_bounds(4, 0, 4));
+        assert!(indirect_range_in_bounds(4, 4, 0));
+        assert!(!indirect_range_in_bounds(4, 4, 1));
+        assert!(!indirect_range_in_bounds(4, 2, 3));
+        assert!(!indirect_range_in_bounds(4, usize::MAX, 2));
+    }
+}
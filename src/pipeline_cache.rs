@@ -0,0 +1,279 @@
+use std::collections::HashMap;
+use std::any::TypeId;
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
+use core::num::NonZeroU32;
+use anyhow::Result;
+use crate::*;
+
+///
+/// Hashable version of a wgpu::VertexBufferLayout.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct VertexBufferLayoutKey{
+    pub array_stride: wgpu::BufferAddress,
+    pub step_mode: wgpu::VertexStepMode,
+    pub attributes: Vec<wgpu::VertexAttribute>,
+}
+
+impl From<&wgpu::VertexBufferLayout<'_>> for VertexBufferLayoutKey{
+    fn from(layout: &wgpu::VertexBufferLayout) -> Self{
+        Self{
+            array_stride: layout.array_stride,
+            step_mode: layout.step_mode,
+            attributes: layout.attributes.to_vec(),
+        }
+    }
+}
+
+///
+/// Hashable version of a wgpu::DepthStencilState.
+/// The floating point depth bias values are compared by their bits.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct DepthStencilKey{
+    pub format: wgpu::TextureFormat,
+    pub depth_write_enabled: bool,
+    pub depth_compare: wgpu::CompareFunction,
+    pub stencil: wgpu::StencilState,
+    pub bias_constant: i32,
+    pub bias_slope_scale: u32,
+    pub bias_clamp: u32,
+}
+
+impl From<&wgpu::DepthStencilState> for DepthStencilKey{
+    fn from(state: &wgpu::DepthStencilState) -> Self{
+        Self{
+            format: state.format,
+            depth_write_enabled: state.depth_write_enabled,
+            depth_compare: state.depth_compare,
+            stencil: state.stencil.clone(),
+            bias_constant: state.bias.constant,
+            bias_slope_scale: state.bias.slope_scale.to_bits(),
+            bias_clamp: state.bias.clamp.to_bits(),
+        }
+    }
+}
+
+///
+/// Everything the SPIR-V of a ShaderModule is compiled from.
+/// sources contains the main source followed by the included files in the order they were
+/// included.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct ShaderKey{
+    pub sources: Vec<String>,
+    pub kind: u32,
+    pub entry_point: String,
+    pub defines: Vec<(String, String)>,
+}
+
+impl ShaderKey{
+    pub fn new(sources: Vec<String>, kind: shaderc::ShaderKind, entry_point: &str, defines: &[(&str, &str)]) -> Self{
+        Self{
+            sources,
+            kind: kind as u32,
+            entry_point: entry_point.to_string(),
+            defines: defines.iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        }
+    }
+}
+
+///
+/// The bind group layout entries and push constant ranges of a PipelineLayout.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct LayoutContentKey{
+    pub entries: Vec<Vec<wgpu::BindGroupLayoutEntry>>,
+    pub push_const_ranges: Vec<(wgpu::ShaderStages, Range<u32>, TypeId)>,
+}
+
+///
+/// Identifies a PipelineLayout in a PipelineCache.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum PipelineLayoutKey{
+    /// The bind group layout entries and push constant ranges.
+    Content(Arc<LayoutContentKey>),
+    /// A layout created from bind group layouts without entries, identical only to itself.
+    Unique(binding::ResourceId),
+}
+
+impl PipelineLayoutKey{
+    pub fn content(entries: &[&[wgpu::BindGroupLayoutEntry]], push_const_ranges: &[PushConstantRange]) -> Self{
+        Self::Content(Arc::new(LayoutContentKey{
+            entries: entries.iter()
+                .map(|entries| entries.to_vec())
+                .collect(),
+            push_const_ranges: push_const_ranges.iter()
+                .map(|range| (range.stages, range.range.clone(), range.type_id))
+                .collect(),
+        }))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct FragmentKey{
+    pub shader: Arc<ShaderKey>,
+    pub entry_point: String,
+    pub targets: Vec<wgpu::ColorTargetState>,
+}
+
+///
+/// Everything that describes a RenderPipeline except for its label.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct RenderPipelineKey{
+    pub layout: Option<PipelineLayoutKey>,
+    pub vertex_shader: Arc<ShaderKey>,
+    pub vertex_entry_point: String,
+    pub vertex_buffer_layouts: Vec<VertexBufferLayoutKey>,
+    pub fragment: Option<FragmentKey>,
+    pub primitive: wgpu::PrimitiveState,
+    pub depth_stencil: Option<DepthStencilKey>,
+    pub multisample: wgpu::MultisampleState,
+    pub multiview: Option<NonZeroU32>,
+}
+
+///
+/// Everything that describes a ComputePipeline except for its label.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct ComputePipelineKey{
+    pub layout: Option<PipelineLayoutKey>,
+    pub shader: Arc<ShaderKey>,
+    pub entry_point: String,
+    pub workgroup_size: Option<[u32; 3]>,
+}
+
+///
+/// Statistics of a PipelineCache.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PipelineCacheStats{
+    /// Number of requests that returned an already created pipeline.
+    pub hits: u64,
+    /// Number of requests that created a new pipeline.
+    pub misses: u64,
+    pub render_pipelines: usize,
+    pub compute_pipelines: usize,
+}
+
+#[derive(Default)]
+struct PipelineCacheInner{
+    render_pipelines: HashMap<RenderPipelineKey, Arc<RenderPipeline>>,
+    compute_pipelines: HashMap<ComputePipelineKey, Arc<ComputePipeline>>,
+    hits: u64,
+    misses: u64,
+}
+
+///
+/// A cache that deduplicates pipelines created from identical builder configurations.
+///
+/// Shader modules are identified by their sources, defines and entry point and pipeline layouts
+/// by their bind group layout entries and push constants, so separately created but identical
+/// modules and layouts share their pipelines.
+/// Layouts created with PipelineLayout::try_new from bare wgpu::BindGroupLayouts are only
+/// identical to themselves.
+/// The label of a builder is not part of its identity, the label of the first pipeline is used.
+///
+/// The cache keeps every pipeline alive until it is evicted. Call evict_unused regularly,
+/// for example after reloading shaders, to drop the pipelines that are only referenced by the
+/// cache.
+///
+/// ```ignore
+/// let pipeline: Arc<RenderPipeline> = gpu.pipeline_cache.render(&gpu.device,
+///     RenderPipelineBuilder::new(&vshader, &fshader)
+///         .set_layout(&layout)
+///         .push_target_replace(wgpu::TextureFormat::Rgba8Unorm)
+/// );
+/// ```
+///
+#[derive(Default)]
+pub struct PipelineCache{
+    inner: Mutex<PipelineCacheInner>,
+}
+
+impl PipelineCache{
+    pub fn new() -> Self{
+        Self::default()
+    }
+
+    ///
+    /// Returns the RenderPipeline described by the builder, building it if no identical
+    /// pipeline has been built with this cache.
+    ///
+    pub fn render(&self, device: &wgpu::Device, builder: RenderPipelineBuilder) -> Arc<RenderPipeline>{
+        self.try_render(device, builder)
+            .unwrap_or_else(|err| panic!("Invalid render pipeline: {}", err))
+    }
+
+    pub fn try_render(&self, device: &wgpu::Device, builder: RenderPipelineBuilder) -> Result<Arc<RenderPipeline>>{
+        let key = builder.cache_key();
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(pipeline) = inner.render_pipelines.get(&key){
+            let pipeline = pipeline.clone();
+            inner.hits += 1;
+            return Ok(pipeline);
+        }
+        let pipeline = Arc::new(builder.try_build(device)?);
+        inner.misses += 1;
+        inner.render_pipelines.insert(key, pipeline.clone());
+        Ok(pipeline)
+    }
+
+    ///
+    /// Returns the ComputePipeline described by the builder, building it if no identical
+    /// pipeline has been built with this cache.
+    ///
+    pub fn compute(&self, device: &wgpu::Device, builder: ComputePipelineBuilder) -> Arc<ComputePipeline>{
+        self.try_compute(device, builder)
+            .unwrap_or_else(|err| panic!("Invalid compute pipeline: {}", err))
+    }
+
+    pub fn try_compute(&self, device: &wgpu::Device, mut builder: ComputePipelineBuilder) -> Result<Arc<ComputePipeline>>{
+        let key = builder.cache_key();
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(pipeline) = inner.compute_pipelines.get(&key){
+            let pipeline = pipeline.clone();
+            inner.hits += 1;
+            return Ok(pipeline);
+        }
+        let pipeline = Arc::new(builder.try_build(device)?);
+        inner.misses += 1;
+        inner.compute_pipelines.insert(key, pipeline.clone());
+        Ok(pipeline)
+    }
+
+    pub fn stats(&self) -> PipelineCacheStats{
+        let inner = self.inner.lock().unwrap();
+        PipelineCacheStats{
+            hits: inner.hits,
+            misses: inner.misses,
+            render_pipelines: inner.render_pipelines.len(),
+            compute_pipelines: inner.compute_pipelines.len(),
+        }
+    }
+
+    ///
+    /// Removes the pipelines that are not referenced outside of the cache and returns their
+    /// number.
+    ///
+    pub fn evict_unused(&self) -> usize{
+        let mut inner = self.inner.lock().unwrap();
+        let len = inner.render_pipelines.len() + inner.compute_pipelines.len();
+        inner.render_pipelines.retain(|_, pipeline| Arc::strong_count(pipeline) > 1);
+        inner.compute_pipelines.retain(|_, pipeline| Arc::strong_count(pipeline) > 1);
+        len - inner.render_pipelines.len() - inner.compute_pipelines.len()
+    }
+
+    ///
+    /// Removes all pipelines from the cache and resets the statistics.
+    /// Pipelines that are still referenced elsewhere stay alive.
+    ///
+    pub fn clear(&self){
+        *self.inner.lock().unwrap() = PipelineCacheInner::default();
+    }
+}
@@ -1,14 +1,40 @@
+use anyhow::*;
+use std::any::TypeId;
+use std::borrow::Cow;
+use crate::utils::Align;
+use std::marker::PhantomData;
+use std::ops::Range;
 
 ///
-/// A trait implemented by all types that can be used as push constants.
-///
-/// By default all types that derive bytemuck::Pod can be push constants.
+/// The layout of a single push constant before it is placed in a PipelineLayout.
+/// It records the type it was created from so that writes can be checked against it.
 ///
+#[derive(Clone, Debug)]
 pub struct PushConstantLayout{
     pub stages: wgpu::ShaderStages,
     pub size: u32,
+    pub type_id: TypeId,
+    pub type_name: &'static str,
+    pub offset: Option<u32>,
+}
+
+impl PushConstantLayout{
+    ///
+    /// Places the push constant at an explicit byte offset for example to match a
+    /// `layout(offset = N)` qualifier in glsl.
+    /// Otherwise it is placed after the previous push constant.
+    ///
+    pub fn with_offset(mut self, offset: u32) -> Self{
+        self.offset = Some(offset);
+        self
+    }
 }
 
+///
+/// A trait implemented by all types that can be used as push constants.
+///
+/// By default all types that derive bytemuck::Pod can be push constants.
+///
 pub trait PushConstant: bytemuck::Pod{
     fn push_const_layout(stages: wgpu::ShaderStages) -> PushConstantLayout;
     fn as_slice8(&self) -> &[u8]{
@@ -21,11 +47,415 @@ impl<T: bytemuck::Pod> PushConstant for T{
         PushConstantLayout{
             stages,
             size: std::mem::size_of::<T>() as u32,
+            type_id: TypeId::of::<T>(),
+            type_name: std::any::type_name::<T>(),
+            offset: None,
+        }
+    }
+}
+
+///
+/// A push constant range placed in a PipelineLayout together with the type it holds.
+///
+#[derive(Clone, Debug)]
+pub struct PushConstantRange{
+    pub stages: wgpu::ShaderStages,
+    pub range: Range<u32>,
+    pub type_id: TypeId,
+    pub type_name: &'static str,
+}
+
+impl PushConstantRange{
+    ///
+    /// Returns an error if C is not the type this range was created with.
+    ///
+    pub fn check<C: PushConstant>(&self) -> Result<()>{
+        ensure!(
+            self.type_id == TypeId::of::<C>(),
+            "push constant range {:?} holds a {} but a {} was provided",
+            self.range, self.type_name, std::any::type_name::<C>()
+        );
+        Ok(())
+    }
+}
+
+impl From<&PushConstantRange> for wgpu::PushConstantRange{
+    fn from(range: &PushConstantRange) -> Self {
+        wgpu::PushConstantRange{
+            stages: range.stages,
+            range: range.range.clone(),
+        }
+    }
+}
+
+/// The shader stages that can have push constants.
+const PUSH_CONSTANT_STAGES: [wgpu::ShaderStages; 3] = [
+    wgpu::ShaderStages::VERTEX,
+    wgpu::ShaderStages::FRAGMENT,
+    wgpu::ShaderStages::COMPUTE,
+];
+
+///
+/// The result of packing push constant layouts.
+///
+/// ranges holds one entry per push constant in the order of the layouts, with the stages that
+/// have to be passed when setting it.
+/// wgpu_ranges holds the ranges for the wgpu::PipelineLayout in which every stage appears at most once.
+///
+#[derive(Clone, Debug, Default)]
+pub struct PushConstantPacking{
+    pub ranges: Vec<PushConstantRange>,
+    pub wgpu_ranges: Vec<wgpu::PushConstantRange>,
+}
+
+///
+/// Packs push constant layouts into ranges.
+///
+/// Push constants without explicit offset are placed after the previous one aligned to 4 bytes.
+/// A push constant can be visible to multiple stages for example VERTEX | FRAGMENT.
+/// The ranges for wgpu span all push constants visible to a stage, stages with the same span
+/// share one range and ranges of different stages may overlap.
+///
+/// Returns an error if offsets are not aligned to 4 bytes, push constants alias each other or the
+/// total size exceeds limits.max_push_constant_size.
+///
+pub fn pack_push_consts(layouts: &[PushConstantLayout], limits: &wgpu::Limits) -> Result<PushConstantPacking>{
+    let mut offset = 0;
+    let mut ranges: Vec<PushConstantRange> = Vec::with_capacity(layouts.len());
+    for layout in layouts{
+        ensure!(!layout.stages.is_empty(), "push constant {} is not visible to any stage", layout.type_name);
+        ensure!(
+            (layout.stages & !wgpu::ShaderStages::all()).is_empty(),
+            "push constant {} has invalid stages {:?}", layout.type_name, layout.stages
+        );
+
+        let start = match layout.offset{
+            Some(start) => {
+                ensure!(
+                    start.align_floor(wgpu::PUSH_CONSTANT_ALIGNMENT) == start,
+                    "offset {} of push constant {} is not aligned to {} bytes",
+                    start, layout.type_name, wgpu::PUSH_CONSTANT_ALIGNMENT
+                );
+                start
+            },
+            None => offset,
+        };
+        let range = start..(start + layout.size.align_ceil(wgpu::PUSH_CONSTANT_ALIGNMENT));
+
+        if let Some(other) = ranges.iter().find(|other| other.range.start < range.end && range.start < other.range.end){
+            bail!("push constant {} at {:?} overlaps {} at {:?}", layout.type_name, range, other.type_name, other.range);
+        }
+
+        offset = offset.max(range.end);
+        ranges.push(PushConstantRange{
+            stages: layout.stages,
+            range,
+            type_id: layout.type_id,
+            type_name: layout.type_name,
+        });
+    }
+
+    ensure!(
+        offset <= limits.max_push_constant_size,
+        "push constants need {} bytes but the device only supports {}",
+        offset, limits.max_push_constant_size
+    );
+
+    // The byte range visible to each stage.
+    let spans: Vec<(wgpu::ShaderStages, Range<u32>)> = PUSH_CONSTANT_STAGES.iter()
+        .filter_map(|stage|{
+            ranges.iter()
+                .filter(|range| range.stages.contains(*stage))
+                .map(|range| range.range.clone())
+                .reduce(|a, b| a.start.min(b.start)..a.end.max(b.end))
+                .map(|span| (*stage, span))
+        }).collect();
+
+    let mut wgpu_ranges: Vec<wgpu::PushConstantRange> = Vec::new();
+    for (stage, span) in spans.iter(){
+        match wgpu_ranges.iter_mut().find(|x| x.range == *span){
+            Some(wgpu_range) => wgpu_range.stages |= *stage,
+            None => wgpu_ranges.push(wgpu::PushConstantRange{
+                stages: *stage,
+                range: span.clone(),
+            }),
+        }
+    }
+
+    // Setting a push constant requires the stages of all ranges overlapping it.
+    for range in ranges.iter_mut(){
+        range.stages = wgpu_ranges.iter()
+            .filter(|x| x.range.start < range.range.end && range.range.start < x.range.end)
+            .fold(wgpu::ShaderStages::empty(), |stages, x| stages | x.stages);
+    }
+
+    Ok(PushConstantPacking{
+        ranges,
+        wgpu_ranges,
+    })
+}
+
+///
+/// Returns the bytes of a push constant padded to a multiple of 4 bytes as required by
+/// set_push_constants.
+///
+pub(crate) fn push_const_bytes<C: PushConstant>(constant: &C) -> Cow<'_, [u8]>{
+    let bytes = bytemuck::bytes_of(constant);
+    if (bytes.len() as u32).is_multiple_of(wgpu::PUSH_CONSTANT_ALIGNMENT){
+        Cow::Borrowed(bytes)
+    }
+    else{
+        let mut padded = vec![0; (bytes.len() as u32).align_ceil(wgpu::PUSH_CONSTANT_ALIGNMENT) as usize];
+        padded[..bytes.len()].copy_from_slice(bytes);
+        Cow::Owned(padded)
+    }
+}
+
+///
+/// Looks up the range at index and checks that it holds a C.
+///
+pub(crate) fn push_const_range<C: PushConstant>(ranges: &[PushConstantRange], index: usize) -> Result<&PushConstantRange>{
+    let range = ranges.get(index)
+        .ok_or_else(|| anyhow!("push constant index {} out of range, the layout has {} push constants", index, ranges.len()))?;
+    range.check::<C>()?;
+    Ok(range)
+}
+
+///
+/// A push constant index that carries the type of its push constant.
+/// It is created by checking the type once against a PipelineLayout so that setting the push
+/// constant afterwards cannot fail.
+///
+/// ```ignore
+/// let consts_slot = pipeline.push_const_slot::<Consts>(0).unwrap();
+///
+/// rpass_ppl.set_push_const_slot(consts_slot, &consts);
+/// ```
+///
+#[derive(Debug)]
+pub struct PushConstantSlot<C: PushConstant>{
+    pub index: usize,
+    _ty: PhantomData<C>,
+}
+
+impl<C: PushConstant> Clone for PushConstantSlot<C>{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C: PushConstant> Copy for PushConstantSlot<C>{}
+
+impl<C: PushConstant> PushConstantSlot<C>{
+    ///
+    /// Checks the type of the push constant at index in the ranges and returns a slot for it.
+    ///
+    pub fn new(ranges: &[PushConstantRange], index: usize) -> Result<Self>{
+        push_const_range::<C>(ranges, index)?;
+        Ok(Self{
+            index,
+            _ty: PhantomData,
+        })
+    }
+
+    ///
+    /// Creates a slot without checking the type against a layout.
+    /// Used by pipeline_layout!, which creates the layout from the same types as its slots.
+    ///
+    #[doc(hidden)]
+    pub fn new_unchecked(index: usize) -> Self{
+        Self{
+            index,
+            _ty: PhantomData,
         }
     }
+
+    ///
+    /// Returns the range of this slot in the ranges it was created from.
+    ///
+    #[inline]
+    pub(crate) fn range<'r>(&self, ranges: &'r [PushConstantRange]) -> &'r PushConstantRange{
+        let range = &ranges[self.index];
+        debug_assert!(range.check::<C>().is_ok(), "push constant slot used with a different pipeline layout");
+        range
+    }
+}
+
+///
+/// A CPU side copy of all push constants of a pipeline layout.
+/// Push constants can be written individually and then be set in a pass all at once with
+/// set_push_consts.
+///
+/// ```ignore
+/// let mut consts = PushConstantVec::new(&pipeline.push_const_ranges);
+/// consts.set(0, &Consts{color: [1.0, 0.0, 0.0, 1.0]}).unwrap();
+///
+/// rpass_ppl.set_push_consts(&consts);
+/// ```
+///
+#[derive(Clone, Debug)]
+pub struct PushConstantVec{
+    pub ranges: Vec<PushConstantRange>,
+    content: Vec<u8>,
+}
+
+impl PushConstantVec{
+    pub fn new(ranges: &[PushConstantRange]) -> Self{
+        let size = ranges.iter().map(|x| x.range.end).max().unwrap_or(0);
+        Self{
+            ranges: Vec::from(ranges),
+            content: vec![0; size as usize],
+        }
+    }
+
+    ///
+    /// Writes the push constant at index returning an error if C has the wrong type.
+    ///
+    pub fn set<C: PushConstant>(&mut self, index: usize, constant: &C) -> Result<()>{
+        let range = push_const_range::<C>(&self.ranges, index)?.range.clone();
+        let bytes = bytemuck::bytes_of(constant);
+        self.content[range.start as usize..range.start as usize + bytes.len()].copy_from_slice(bytes);
+        Ok(())
+    }
+
+    pub fn set_slot<C: PushConstant>(&mut self, slot: PushConstantSlot<C>, constant: &C){
+        let start = slot.range(&self.ranges).range.start as usize;
+        let bytes = bytemuck::bytes_of(constant);
+        self.content[start..start + bytes.len()].copy_from_slice(bytes);
+    }
+
+    ///
+    /// Reads the push constant at index returning an error if C has the wrong type.
+    ///
+    pub fn get<C: PushConstant>(&self, index: usize) -> Result<C>{
+        let start = push_const_range::<C>(&self.ranges, index)?.range.start as usize;
+        Ok(bytemuck::pod_read_unaligned(&self.content[start..start + std::mem::size_of::<C>()]))
+    }
+
+    ///
+    /// Returns the bytes of the range at index.
+    ///
+    pub fn range_bytes(&self, index: usize) -> &[u8]{
+        let range = &self.ranges[index].range;
+        &self.content[range.start as usize..range.end as usize]
+    }
 }
 
-pub struct PushConstantVec<T: bytemuck::Pod>{
-    pub layout: PushConstantLayout,
-    pub content: T,
+#[cfg(test)]
+mod test{
+    use super::*;
+
+    fn ranges() -> Vec<PushConstantRange>{
+        let a = u32::push_const_layout(wgpu::ShaderStages::VERTEX);
+        let b = <[f32; 4]>::push_const_layout(wgpu::ShaderStages::FRAGMENT);
+        vec![
+            PushConstantRange{stages: a.stages, range: 0..4, type_id: a.type_id, type_name: a.type_name},
+            PushConstantRange{stages: b.stages, range: 4..20, type_id: b.type_id, type_name: b.type_name},
+        ]
+    }
+
+    #[test]
+    fn test_push_const_type_check(){
+        let ranges = ranges();
+
+        assert!(PushConstantSlot::<u32>::new(&ranges, 0).is_ok());
+        assert!(PushConstantSlot::<f32>::new(&ranges, 0).is_err());
+        assert!(PushConstantSlot::<u32>::new(&ranges, 2).is_err());
+    }
+
+    fn limits() -> wgpu::Limits{
+        wgpu::Limits{
+            max_push_constant_size: 128,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_pack_align(){
+        let packing = pack_push_consts(&[
+            <[u8; 6]>::push_const_layout(wgpu::ShaderStages::VERTEX),
+            u32::push_const_layout(wgpu::ShaderStages::VERTEX),
+        ], &limits()).unwrap();
+
+        assert_eq!(packing.ranges[0].range, 0..8);
+        assert_eq!(packing.ranges[1].range, 8..12);
+        assert_eq!(packing.wgpu_ranges.len(), 1);
+        assert_eq!(packing.wgpu_ranges[0].stages, wgpu::ShaderStages::VERTEX);
+        assert_eq!(packing.wgpu_ranges[0].range, 0..12);
+    }
+
+    #[test]
+    fn test_pack_shared_stages(){
+        let packing = pack_push_consts(&[
+            <[f32; 4]>::push_const_layout(wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT),
+        ], &limits()).unwrap();
+
+        assert_eq!(packing.wgpu_ranges.len(), 1);
+        assert_eq!(packing.wgpu_ranges[0].stages, wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT);
+        assert_eq!(packing.wgpu_ranges[0].range, 0..16);
+        assert_eq!(packing.ranges[0].stages, wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT);
+    }
+
+    #[test]
+    fn test_pack_overlapping_stages(){
+        let packing = pack_push_consts(&[
+            <[f32; 16]>::push_const_layout(wgpu::ShaderStages::VERTEX),
+            <[f32; 4]>::push_const_layout(wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT),
+        ], &limits()).unwrap();
+
+        // Every stage may only appear in one range.
+        assert_eq!(packing.wgpu_ranges.len(), 2);
+        assert_eq!(packing.wgpu_ranges[0].stages, wgpu::ShaderStages::VERTEX);
+        assert_eq!(packing.wgpu_ranges[0].range, 0..80);
+        assert_eq!(packing.wgpu_ranges[1].stages, wgpu::ShaderStages::FRAGMENT);
+        assert_eq!(packing.wgpu_ranges[1].range, 64..80);
+
+        assert_eq!(packing.ranges[0].stages, wgpu::ShaderStages::VERTEX);
+        assert_eq!(packing.ranges[1].stages, wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT);
+    }
+
+    #[test]
+    fn test_pack_explicit_offset(){
+        let packing = pack_push_consts(&[
+            u32::push_const_layout(wgpu::ShaderStages::VERTEX).with_offset(16),
+            u32::push_const_layout(wgpu::ShaderStages::FRAGMENT).with_offset(0),
+        ], &limits()).unwrap();
+
+        assert_eq!(packing.ranges[0].range, 16..20);
+        assert_eq!(packing.ranges[1].range, 0..4);
+
+        assert!(pack_push_consts(&[
+            u32::push_const_layout(wgpu::ShaderStages::VERTEX).with_offset(2),
+        ], &limits()).is_err());
+
+        assert!(pack_push_consts(&[
+            <[u32; 2]>::push_const_layout(wgpu::ShaderStages::VERTEX),
+            u32::push_const_layout(wgpu::ShaderStages::FRAGMENT).with_offset(4),
+        ], &limits()).is_err());
+    }
+
+    #[test]
+    fn test_pack_limits(){
+        assert!(pack_push_consts(&[
+            <[f32; 32]>::push_const_layout(wgpu::ShaderStages::VERTEX),
+        ], &limits()).is_ok());
+
+        assert!(pack_push_consts(&[
+            <[f32; 32]>::push_const_layout(wgpu::ShaderStages::VERTEX),
+            u32::push_const_layout(wgpu::ShaderStages::FRAGMENT),
+        ], &limits()).is_err());
+    }
+
+    #[test]
+    fn test_push_const_vec(){
+        let mut consts = PushConstantVec::new(&ranges());
+
+        consts.set(0, &7u32).unwrap();
+        consts.set(1, &[1.0f32, 2.0, 3.0, 4.0]).unwrap();
+        assert!(consts.set(1, &7u32).is_err());
+
+        assert_eq!(consts.get::<u32>(0).unwrap(), 7);
+        assert_eq!(consts.get::<[f32; 4]>(1).unwrap(), [1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(consts.range_bytes(0), bytemuck::bytes_of(&7u32));
+    }
 }
@@ -0,0 +1,228 @@
+use crate::*;
+use anyhow::Result;
+
+use core::num::NonZeroU32;
+
+///
+/// A builder for a RenderBundleEncoder.
+///
+/// The color formats, depth stencil format and sample count have to match the RenderPass in which
+/// the resulting bundle is executed.
+///
+/// ```ignore
+/// let mut bundle_encoder = RenderBundleBuilder::new()
+///     .push_color_format(wgpu::TextureFormat::Rgba8Unorm)
+///     .begin(&gpu.device);
+///
+/// {
+///     let mut bundle_ppl = bundle_encoder.set_pipeline(&pipeline);
+///     bundle_ppl.set_push_const(0, &consts).unwrap();
+///     bundle_ppl.set_vertex_buffer(0, vertices.slice(..));
+///     bundle_ppl.set_index_buffer(indices.slice(..));
+///     bundle_ppl.draw_indexed(.., 0, 0..1).unwrap();
+/// }
+///
+/// let bundle = bundle_encoder.finish(None);
+///
+/// let mut rpass = RenderPassBuilder::new()
+///     .push_color_attachment(dst.color_attachment_clear())
+///     .begin(encoder, None);
+/// rpass.execute_bundles([&bundle]);
+/// ```
+///
+pub struct RenderBundleBuilder<'rbb>{
+    label: wgpu::Label<'rbb>,
+    color_formats: Vec<wgpu::TextureFormat>,
+    depth_stencil: Option<wgpu::RenderBundleDepthStencil>,
+    sample_count: u32,
+    multiview: Option<NonZeroU32>,
+}
+
+impl<'rbb> Default for RenderBundleBuilder<'rbb>{
+    fn default() -> Self {
+        Self{
+            label: None,
+            color_formats: Vec::new(),
+            depth_stencil: None,
+            sample_count: 1,
+            multiview: None,
+        }
+    }
+}
+
+impl<'rbb> RenderBundleBuilder<'rbb>{
+    pub fn new() -> Self{
+        Self::default()
+    }
+
+    ///
+    /// Pushes the format of a color attachment.
+    ///
+    /// Has to be pushed in the same order as the color attachments of the RenderPass.
+    ///
+    #[inline]
+    pub fn push_color_format(mut self, format: wgpu::TextureFormat) -> Self{
+        self.color_formats.push(format);
+        self
+    }
+
+    #[inline]
+    pub fn set_depth_stencil(mut self, depth_stencil: Option<wgpu::RenderBundleDepthStencil>) -> Self{
+        self.depth_stencil = depth_stencil;
+        self
+    }
+
+    #[inline]
+    pub fn set_depth_stencil_less32(mut self) -> Self{
+        self.depth_stencil = Some(wgpu::RenderBundleDepthStencil{
+            format: wgpu::TextureFormat::Depth32Float,
+            depth_read_only: false,
+            stencil_read_only: true,
+        });
+        self
+    }
+
+    #[inline]
+    pub fn set_sample_count(mut self, sample_count: u32) -> Self{
+        self.sample_count = sample_count;
+        self
+    }
+
+    #[inline]
+    pub fn set_multiview(mut self, multiview: Option<NonZeroU32>) -> Self{
+        self.multiview = multiview;
+        self
+    }
+
+    #[inline]
+    pub fn set_label(mut self, label: wgpu::Label<'rbb>) -> Self{
+        self.label = label;
+        self
+    }
+
+    pub fn begin(self, device: &'rbb wgpu::Device) -> RenderBundleEncoder<'rbb>{
+        RenderBundleEncoder{
+            encoder: device.create_render_bundle_encoder(&wgpu::RenderBundleEncoderDescriptor{
+                label: self.label,
+                color_formats: &self.color_formats,
+                depth_stencil: self.depth_stencil,
+                sample_count: self.sample_count,
+                multiview: self.multiview,
+            }),
+            index_count: None,
+        }
+    }
+}
+
+///
+/// A wrapper for wgpu::RenderBundleEncoder.
+///
+#[derive(DerefMut)]
+pub struct RenderBundleEncoder<'rb>{
+    #[target]
+    pub encoder: wgpu::RenderBundleEncoder<'rb>,
+    index_count: Option<u32>,
+}
+
+impl<'rb> RenderBundleEncoder<'rb>{
+    pub fn set_pipeline(&mut self, pipeline: &'rb RenderPipeline) -> RenderBundleEncoderPipeline<'rb, '_>{
+        self.encoder.set_pipeline(&pipeline.pipeline);
+        RenderBundleEncoderPipeline{
+            encoder: self,
+            pipeline,
+        }
+    }
+
+    ///
+    /// Finish recording and return the RenderBundle.
+    ///
+    pub fn finish(self, label: wgpu::Label) -> wgpu::RenderBundle{
+        self.encoder.finish(&wgpu::RenderBundleDescriptor{
+            label,
+        })
+    }
+}
+
+///
+/// A RenderBundleEncoder with pipeline needed for push_const offsets.
+///
+pub struct RenderBundleEncoderPipeline<'rb, 'rbr>{
+    pub encoder: &'rbr mut RenderBundleEncoder<'rb>,
+    pub pipeline: &'rb RenderPipeline,
+}
+
+impl<'rb, 'rbr> RenderBundleEncoderPipeline<'rb, 'rbr>{
+    ///
+    /// Sets the bind group at the index of a slot generated by pipeline_layout!.
+    /// The content of the bind group has to match the type of the slot.
+    ///
+    pub fn set_bind_group_slot<B: binding::GetBindGroup + SlotContent>(&mut self, slot: BindGroupSlot<B::Content>, bind_group: &'rb B, offsets: &'rb [wgpu::DynamicOffset]){
+        self.encoder.encoder.set_bind_group(slot.index, bind_group.bind_group(), offsets);
+    }
+
+    pub fn set_bind_group<B: binding::GetBindGroup>(&mut self, index: u32, bind_group: &'rb B, offsets: &'rb [wgpu::DynamicOffset]){
+        self.encoder.encoder.set_bind_group(
+            index,
+            bind_group.bind_group(),
+            offsets
+        );
+    }
+
+    ///
+    /// Sets the push constant at index.
+    /// Returns an error if C is not the type the pipeline layout was created with.
+    ///
+    pub fn set_push_const<C: PushConstant>(&mut self, index: usize, constant: &C) -> Result<()>{
+        let range = push_const_range::<C>(&self.pipeline.push_const_ranges, index)?;
+        self.encoder.encoder.set_push_constants(
+            range.stages,
+            range.range.start,
+            &push_const_bytes(constant));
+        Ok(())
+    }
+
+    ///
+    /// Sets the push constant of a slot whose type has already been checked.
+    ///
+    pub fn set_push_const_slot<C: PushConstant>(&mut self, slot: PushConstantSlot<C>, constant: &C){
+        let range = slot.range(&self.pipeline.push_const_ranges);
+        self.encoder.encoder.set_push_constants(
+            range.stages,
+            range.range.start,
+            &push_const_bytes(constant));
+    }
+
+    ///
+    /// Sets all push constants stored in the PushConstantVec.
+    ///
+    pub fn set_push_consts(&mut self, constants: &PushConstantVec){
+        for (i, range) in constants.ranges.iter().enumerate(){
+            self.encoder.encoder.set_push_constants(
+                range.stages,
+                range.range.start,
+                constants.range_bytes(i));
+        }
+    }
+
+    pub fn set_pipeline(&'rbr mut self, pipeline: &'rb RenderPipeline) -> Self{
+        self.encoder.encoder.set_pipeline(&pipeline.pipeline);
+        Self{
+            encoder: self.encoder,
+            pipeline,
+        }
+    }
+}
+
+impl<'rb, 'rbr> RenderEncoderPipeline<'rb> for RenderBundleEncoderPipeline<'rb, 'rbr>{
+    type Encoder = wgpu::RenderBundleEncoder<'rb>;
+
+    #[inline]
+    fn encoder(&mut self) -> &mut Self::Encoder{
+        &mut self.encoder.encoder
+    }
+
+    #[inline]
+    fn index_count(&mut self) -> &mut Option<u32>{
+        &mut self.encoder.index_count
+    }
+}
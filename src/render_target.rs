@@ -2,13 +2,13 @@
 /// Can be attached as the ColorAttachment of a RenderPass
 ///
 pub trait ColorAttachment{
-    fn color_attachment_clear(&self) -> wgpu::RenderPassColorAttachment;
-    fn color_attachment_clear_with(&self, color: wgpu::Color) -> wgpu::RenderPassColorAttachment;
-    fn color_attachment_load(&self) -> wgpu::RenderPassColorAttachment;
+    fn color_attachment_clear(&self) -> wgpu::RenderPassColorAttachment<'_>;
+    fn color_attachment_clear_with(&self, color: wgpu::Color) -> wgpu::RenderPassColorAttachment<'_>;
+    fn color_attachment_load(&self) -> wgpu::RenderPassColorAttachment<'_>;
 }
 
 impl ColorAttachment for wgpu::TextureView{
-    fn color_attachment_clear(&self) -> wgpu::RenderPassColorAttachment {
+    fn color_attachment_clear(&self) -> wgpu::RenderPassColorAttachment<'_> {
         wgpu::RenderPassColorAttachment{
             view: self,
             resolve_target: None,
@@ -24,7 +24,7 @@ impl ColorAttachment for wgpu::TextureView{
         }
     }
 
-    fn color_attachment_clear_with(&self, color: wgpu::Color) -> wgpu::RenderPassColorAttachment{
+    fn color_attachment_clear_with(&self, color: wgpu::Color) -> wgpu::RenderPassColorAttachment<'_>{
         wgpu::RenderPassColorAttachment{
             view: self,
             resolve_target: None,
@@ -35,7 +35,7 @@ impl ColorAttachment for wgpu::TextureView{
         }
     }
 
-    fn color_attachment_load(&self) -> wgpu::RenderPassColorAttachment{
+    fn color_attachment_load(&self) -> wgpu::RenderPassColorAttachment<'_>{
         wgpu::RenderPassColorAttachment{
             view: self,
             resolve_target: None,
@@ -47,3 +47,41 @@ impl ColorAttachment for wgpu::TextureView{
     }
 }
 
+
+///
+/// Can be attached as the depth stencil attachment of a RenderPass.
+/// Only the depth aspect is written, the stencil aspect is left untouched.
+///
+pub trait DepthAttachment{
+    fn depth_attachment_clear(&self) -> wgpu::RenderPassDepthStencilAttachment<'_>;
+    fn depth_attachment_clear_with(&self, depth: f32) -> wgpu::RenderPassDepthStencilAttachment<'_>;
+    fn depth_attachment_load(&self) -> wgpu::RenderPassDepthStencilAttachment<'_>;
+}
+
+impl DepthAttachment for wgpu::TextureView{
+    fn depth_attachment_clear(&self) -> wgpu::RenderPassDepthStencilAttachment<'_> {
+        self.depth_attachment_clear_with(1.0)
+    }
+
+    fn depth_attachment_clear_with(&self, depth: f32) -> wgpu::RenderPassDepthStencilAttachment<'_> {
+        wgpu::RenderPassDepthStencilAttachment{
+            view: self,
+            depth_ops: Some(wgpu::Operations{
+                load: wgpu::LoadOp::Clear(depth),
+                store: true,
+            }),
+            stencil_ops: None,
+        }
+    }
+
+    fn depth_attachment_load(&self) -> wgpu::RenderPassDepthStencilAttachment<'_> {
+        wgpu::RenderPassDepthStencilAttachment{
+            view: self,
+            depth_ops: Some(wgpu::Operations{
+                load: wgpu::LoadOp::Load,
+                store: true,
+            }),
+            stencil_ops: None,
+        }
+    }
+}
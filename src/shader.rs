@@ -1,5 +1,6 @@
 use std::cell::RefCell;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::str;
 use crate::*;
 use anyhow::*;
@@ -17,10 +18,30 @@ pub struct ShaderModule{
     #[target]
     pub module: wgpu::ShaderModule,
     pub src_files: Vec<PathBuf>,
+    /// The reflected interface of the entry point or None if the SPIR-V could not be reflected.
+    pub interface: Option<ShaderInterface>,
+    cache_key: Arc<pipeline_cache::ShaderKey>,
 }
 
 impl ShaderModule{
+    ///
+    /// Returns the source, included files, shader kind, entry point and defines the module was
+    /// compiled from.
+    /// It is used to identify identical modules in a PipelineCache.
+    ///
+    #[inline]
+    pub(crate) fn cache_key(&self) -> Arc<pipeline_cache::ShaderKey>{
+        self.cache_key.clone()
+    }
+
     pub fn from_src(device: &wgpu::Device, src: &str, kind: shaderc::ShaderKind, entry_point: &str, label: Option<&str>) -> Result<Self>{
+        Self::from_src_with_defines(device, src, kind, entry_point, &[], label)
+    }
+
+    ///
+    /// Compiles the source with additional preprocessor definitions.
+    ///
+    pub fn from_src_with_defines(device: &wgpu::Device, src: &str, kind: shaderc::ShaderKind, entry_point: &str, defines: &[(&str, &str)], label: Option<&str>) -> Result<Self>{
         let mut compiler = shaderc::Compiler::new().ok_or(anyhow!("error creating compiler"))?;
         let mut options = shaderc::CompileOptions::new().ok_or(anyhow!("error creating shaderc options"))?;
 
@@ -32,6 +53,9 @@ impl ShaderModule{
         options.add_macro_definition("VERTEX_SHADER", Some(if kind == shaderc::ShaderKind::Vertex {"1"} else {"0"}));
         options.add_macro_definition("FRAGMENT_SHADER", Some(if kind == shaderc::ShaderKind::Fragment {"1"} else {"0"}));
         options.add_macro_definition("COMPUTE_SHADER", Some(if kind == shaderc::ShaderKind::Compute {"1"} else {"0"}));
+        for (name, value) in defines{
+            options.add_macro_definition(name, Some(value));
+        }
 
         let spirv = match label{
             Some(label) => compiler.compile_into_spirv(src, kind, label, entry_point, Some(&options))?,
@@ -46,14 +70,17 @@ impl ShaderModule{
         Ok(ShaderModule{
             module,
             src_files: Vec::new(),
+            interface: ShaderInterface::reflect(spirv.as_binary_u8(), entry_point).ok(),
+            cache_key: Arc::new(pipeline_cache::ShaderKey::new(vec![src.to_string()], kind, entry_point, defines)),
         })
     }
 
     pub fn load(device: &wgpu::Device, path: &Path, kind: shaderc::ShaderKind, entry_point: &str, label: Option<&str>) -> Result<Self>{
 
         let src_files = RefCell::new(vec![PathBuf::from(path).canonicalize().unwrap()]);
+        let sources = RefCell::new(Vec::new());
 
-        let module = {
+        let (module, interface) = {
             let path = src_files.borrow();
             let dir = path[0].parent().unwrap();
             let src = match std::fs::read_to_string(&src_files.borrow()[0]){
@@ -75,6 +102,8 @@ impl ShaderModule{
             options.add_macro_definition("FRAGMENT_SHADER", Some(if kind == shaderc::ShaderKind::Fragment {"1"} else {"0"}));
             options.add_macro_definition("COMPUTE_SHADER", Some(if kind == shaderc::ShaderKind::Compute {"1"} else {"0"}));
 
+            sources.borrow_mut().push(src.clone());
+
             options.set_include_callback(|name, include_type, source_file, _depth| {
                 let path = if include_type == shaderc::IncludeType::Relative{
                     Path::new(Path::new(source_file).parent().unwrap()).join(name)
@@ -85,6 +114,7 @@ impl ShaderModule{
                 match std::fs::read_to_string(&path){
                     std::result::Result::Ok(glsl_code) => {
                         src_files.borrow_mut().push(path.canonicalize().unwrap());
+                        sources.borrow_mut().push(glsl_code.clone());
                         std::result::Result::Ok(shaderc::ResolvedInclude{
                             resolved_name: String::from(name),
                             content: glsl_code,
@@ -103,11 +133,13 @@ impl ShaderModule{
                 label,
                 source: wgpu::ShaderSource::SpirV(Cow::from(spirv.as_binary()))
             });
-            module
+            (module, ShaderInterface::reflect(spirv.as_binary_u8(), entry_point).ok())
         };
         Ok(ShaderModule{
             module,
             src_files: src_files.into_inner(),
+            interface,
+            cache_key: Arc::new(pipeline_cache::ShaderKey::new(sources.into_inner(), kind, entry_point, &[])),
         })
     }
 }
@@ -160,6 +192,32 @@ impl ComputeShader{
             module: ShaderModule::from_src(device, src, shaderc::ShaderKind::Compute, DEFAULT_ENTRY_POINT, label)?,
         })
     }
+
+    ///
+    /// Compiles the source with WORKGROUP_SIZE_X, WORKGROUP_SIZE_Y and WORKGROUP_SIZE_Z defined
+    /// so that the local size can be specialized at compile time:
+    ///
+    /// ```glsl
+    /// layout(local_size_x = WORKGROUP_SIZE_X, local_size_y = WORKGROUP_SIZE_Y, local_size_z = WORKGROUP_SIZE_Z) in;
+    /// ```
+    ///
+    pub fn from_src_with_workgroup_size(device: &wgpu::Device, src: &str, workgroup_size: [u32; 3], label: Option<&str>) -> Result<Self>{
+        let [x, y, z] = workgroup_size.map(|size| size.to_string());
+        Self::from_src_with_defines(device, src, &[
+            ("WORKGROUP_SIZE_X", &x),
+            ("WORKGROUP_SIZE_Y", &y),
+            ("WORKGROUP_SIZE_Z", &z),
+        ], label)
+    }
+
+    ///
+    /// Compiles the source with additional preprocessor definitions.
+    ///
+    pub fn from_src_with_defines(device: &wgpu::Device, src: &str, defines: &[(&str, &str)], label: Option<&str>) -> Result<Self>{
+        Ok(Self{
+            module: ShaderModule::from_src_with_defines(device, src, shaderc::ShaderKind::Compute, DEFAULT_ENTRY_POINT, defines, label)?,
+        })
+    }
     pub fn load(device: &wgpu::Device, path: &Path, label: Option<&str>) -> Result<Self>{
         Ok(Self{
             module: ShaderModule::load(device, path, shaderc::ShaderKind::Compute, DEFAULT_ENTRY_POINT, label)?,
@@ -167,3 +225,340 @@ impl ComputeShader{
     }
 }
 
+
+///
+/// The type of an InterfaceVariable.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InterfaceType{
+    Scalar{kind: naga::ScalarKind, width: u8},
+    Vector{size: naga::VectorSize, kind: naga::ScalarKind, width: u8},
+    Matrix{columns: naga::VectorSize, rows: naga::VectorSize, width: u8},
+    Other,
+}
+
+impl From<&naga::TypeInner> for InterfaceType{
+    fn from(ty: &naga::TypeInner) -> Self {
+        match *ty{
+            naga::TypeInner::Scalar{kind, width} => Self::Scalar{kind, width},
+            naga::TypeInner::Vector{size, kind, width} => Self::Vector{size, kind, width},
+            naga::TypeInner::Matrix{columns, rows, width} => Self::Matrix{columns, rows, width},
+            _ => Self::Other,
+        }
+    }
+}
+
+///
+/// A location bound input or output of a shader entry point.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterfaceVariable{
+    pub location: u32,
+    pub ty: InterfaceType,
+}
+
+///
+/// The reflected interface of a shader entry point.
+///
+#[derive(Debug, Clone, Default)]
+pub struct ShaderInterface{
+    pub inputs: Vec<InterfaceVariable>,
+    pub outputs: Vec<InterfaceVariable>,
+    pub workgroup_size: [u32; 3],
+}
+
+impl ShaderInterface{
+    ///
+    /// Reflects the interface of the entry point in the SPIR-V binary.
+    ///
+    pub fn reflect(spirv: &[u8], entry_point: &str) -> Result<Self>{
+        let module = naga::front::spv::parse_u8_slice(spirv, &Default::default())?;
+        let entry_point = module.entry_points.iter()
+            .find(|ep| ep.name == entry_point)
+            .ok_or_else(|| anyhow!("Entry point {} not found", entry_point))?;
+
+        let mut inputs = Vec::new();
+        for argument in entry_point.function.arguments.iter(){
+            Self::push_variables(&module, argument.ty, &argument.binding, &mut inputs);
+        }
+        let mut outputs = Vec::new();
+        if let Some(result) = &entry_point.function.result{
+            Self::push_variables(&module, result.ty, &result.binding, &mut outputs);
+        }
+
+        inputs.sort_by_key(|var| var.location);
+        outputs.sort_by_key(|var| var.location);
+
+        Ok(Self{
+            inputs,
+            outputs,
+            workgroup_size: entry_point.workgroup_size,
+        })
+    }
+
+    ///
+    /// Pushes the location bound variables of an argument or result, including the members of
+    /// structs.
+    ///
+    fn push_variables(module: &naga::Module, ty: naga::Handle<naga::Type>, binding: &Option<naga::Binding>, vars: &mut Vec<InterfaceVariable>){
+        match binding{
+            Some(naga::Binding::Location{location, ..}) => {
+                vars.push(InterfaceVariable{
+                    location: *location,
+                    ty: InterfaceType::from(&module.types[ty].inner),
+                });
+            },
+            Some(naga::Binding::BuiltIn(_)) => {},
+            None => {
+                if let naga::TypeInner::Struct{members, ..} = &module.types[ty].inner{
+                    for member in members{
+                        Self::push_variables(module, member.ty, &member.binding, vars);
+                    }
+                }
+            },
+        }
+    }
+
+    ///
+    /// Checks that every input of next is written by an output of self with the same type.
+    ///
+    pub fn check_outputs_match(&self, next: &ShaderInterface) -> Result<()>{
+        for input in next.inputs.iter(){
+            match self.outputs.iter().find(|output| output.location == input.location){
+                Some(output) => {
+                    ensure!(output.ty == input.ty,
+                        "Output at location {} has type {:?} but the input has type {:?}",
+                        input.location, output.ty, input.ty);
+                },
+                None => {
+                    bail!("Input at location {} is not written by the previous stage", input.location);
+                },
+            }
+        }
+        Ok(())
+    }
+}
+
+///
+/// All shader stages compiled from one GLSL source.
+///
+/// A stage is compiled if its macro (VERTEX_SHADER, FRAGMENT_SHADER or COMPUTE_SHADER) appears
+/// in the source.
+/// The outputs of the vertex stage are checked against the inputs of the fragment stage.
+///
+/// ```ignore
+/// let program = ShaderProgram::from_src(&gpu.device, "
+/// #version 460
+/// #if VERTEX_SHADER
+/// ...
+/// #elif FRAGMENT_SHADER
+/// ...
+/// #endif
+/// ", None)?;
+///
+/// let pipeline = RenderPipelineBuilder::from_program(&program)?
+///     .push_target_replace(wgpu::TextureFormat::Rgba8Unorm)
+///     .build(&gpu.device);
+/// ```
+///
+#[derive(Debug)]
+pub struct ShaderProgram{
+    pub vertex: Option<VertexShader>,
+    pub fragment: Option<FragmentShader>,
+    pub compute: Option<ComputeShader>,
+}
+
+impl ShaderProgram{
+    ///
+    /// Returns true if the stage macro is used in the source outside of comments and #if 0 blocks.
+    ///
+    fn has_stage(src: &str, stage_macro: &str) -> bool{
+        Self::active_source(&Self::strip_comments(src))
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .any(|token| token == stage_macro)
+    }
+
+    ///
+    /// Replaces line and block comments with whitespace keeping the line breaks.
+    ///
+    fn strip_comments(src: &str) -> String{
+        let mut stripped = String::with_capacity(src.len());
+        let mut chars = src.chars().peekable();
+        while let Some(c) = chars.next(){
+            match (c, chars.peek()){
+                ('/', Some('/')) => {
+                    while chars.next_if(|c| *c != '\n').is_some(){}
+                },
+                ('/', Some('*')) => {
+                    chars.next();
+                    let mut prev = ' ';
+                    for c in chars.by_ref(){
+                        if prev == '*' && c == '/'{
+                            break;
+                        }
+                        if c == '\n'{
+                            stripped.push('\n');
+                        }
+                        prev = c;
+                    }
+                    stripped.push(' ');
+                },
+                _ => stripped.push(c),
+            }
+        }
+        stripped
+    }
+
+    ///
+    /// Removes the lines inside of #if 0 blocks up to their #else, #elif or #endif.
+    ///
+    fn active_source(src: &str) -> String{
+        let mut active = String::with_capacity(src.len());
+        // Nesting depth of the conditionals inside of an #if 0 block, 0 if the line is active.
+        let mut disabled_depth = 0;
+        for line in src.lines(){
+            if let Some(directive) = line.trim_start().strip_prefix('#'){
+                let mut words = directive.split_whitespace();
+                match (words.next(), disabled_depth){
+                    (Some("if" | "ifdef" | "ifndef"), 1..) => disabled_depth += 1,
+                    (Some("if"), 0) if words.next() == Some("0") && words.next().is_none() => disabled_depth = 1,
+                    (Some("else" | "elif"), 1) => disabled_depth = 0,
+                    (Some("endif"), 1..) => {
+                        disabled_depth -= 1;
+                        continue;
+                    },
+                    _ => {},
+                }
+            }
+            if disabled_depth == 0{
+                active.push_str(line);
+                active.push('\n');
+            }
+        }
+        active
+    }
+
+    pub fn from_src(device: &wgpu::Device, src: &str, label: Option<&str>) -> Result<Self>{
+        let vertex = match Self::has_stage(src, "VERTEX_SHADER"){
+            true => Some(VertexShader::from_src(device, src, label)?),
+            false => None,
+        };
+        let fragment = match Self::has_stage(src, "FRAGMENT_SHADER"){
+            true => Some(FragmentShader::from_src(device, src, label)?),
+            false => None,
+        };
+        let compute = match Self::has_stage(src, "COMPUTE_SHADER"){
+            true => Some(ComputeShader::from_src(device, src, label)?),
+            false => None,
+        };
+        Self::new(vertex, fragment, compute)
+    }
+
+    pub fn load(device: &wgpu::Device, path: &Path, label: Option<&str>) -> Result<Self>{
+        let src = std::fs::read_to_string(path)
+            .map_err(|err| anyhow!("Failed to read shader file \"{:?}\": {}", path, err))?;
+
+        let vertex = match Self::has_stage(&src, "VERTEX_SHADER"){
+            true => Some(VertexShader::load(device, path, label)?),
+            false => None,
+        };
+        let fragment = match Self::has_stage(&src, "FRAGMENT_SHADER"){
+            true => Some(FragmentShader::load(device, path, label)?),
+            false => None,
+        };
+        let compute = match Self::has_stage(&src, "COMPUTE_SHADER"){
+            true => Some(ComputeShader::load(device, path, label)?),
+            false => None,
+        };
+        Self::new(vertex, fragment, compute)
+    }
+
+    ///
+    /// Creates a program from compiled stages checking that the vertex outputs match the
+    /// fragment inputs.
+    ///
+    pub fn new(vertex: Option<VertexShader>, fragment: Option<FragmentShader>, compute: Option<ComputeShader>) -> Result<Self>{
+        ensure!(vertex.is_some() || fragment.is_some() || compute.is_some(), "ShaderProgram has no stages");
+
+        if let (Some(vertex), Some(fragment)) = (&vertex, &fragment){
+            match (&vertex.interface, &fragment.interface){
+                (Some(vertex_interface), Some(fragment_interface)) => {
+                    vertex_interface.check_outputs_match(fragment_interface)
+                        .map_err(|err| anyhow!("Vertex outputs do not match fragment inputs: {}", err))?;
+                },
+                _ => log::warn!("Could not reflect the shader interfaces, vertex outputs are not checked against fragment inputs"),
+            }
+        }
+
+        Ok(Self{
+            vertex,
+            fragment,
+            compute,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test{
+    use super::*;
+
+    fn vec4() -> InterfaceType{
+        InterfaceType::Vector{size: naga::VectorSize::Quad, kind: naga::ScalarKind::Float, width: 4}
+    }
+
+    #[test]
+    fn interface_match(){
+        let vertex = ShaderInterface{
+            outputs: vec![
+                InterfaceVariable{location: 0, ty: vec4()},
+                InterfaceVariable{location: 1, ty: InterfaceType::Scalar{kind: naga::ScalarKind::Uint, width: 4}},
+            ],
+            ..Default::default()
+        };
+        let fragment = ShaderInterface{
+            inputs: vec![
+                InterfaceVariable{location: 0, ty: vec4()},
+            ],
+            ..Default::default()
+        };
+        assert!(vertex.check_outputs_match(&fragment).is_ok());
+
+        let missing = ShaderInterface{
+            inputs: vec![
+                InterfaceVariable{location: 2, ty: vec4()},
+            ],
+            ..Default::default()
+        };
+        assert!(vertex.check_outputs_match(&missing).is_err());
+
+        let mismatch = ShaderInterface{
+            inputs: vec![
+                InterfaceVariable{location: 1, ty: vec4()},
+            ],
+            ..Default::default()
+        };
+        assert!(vertex.check_outputs_match(&mismatch).is_err());
+    }
+
+    #[test]
+    fn program_stages(){
+        let src = "#if VERTEX_SHADER\n#elif FRAGMENT_SHADER\n#endif";
+        assert!(ShaderProgram::has_stage(src, "VERTEX_SHADER"));
+        assert!(ShaderProgram::has_stage(src, "FRAGMENT_SHADER"));
+        assert!(!ShaderProgram::has_stage(src, "COMPUTE_SHADER"));
+
+        let src = "
+            // #if COMPUTE_SHADER
+            /* #if FRAGMENT_SHADER
+            */
+            #if 0
+            #if VERTEX_SHADER
+            #endif
+            #elif COMPUTE_SHADER
+            #endif
+            ";
+        assert!(!ShaderProgram::has_stage(src, "VERTEX_SHADER"));
+        assert!(!ShaderProgram::has_stage(src, "FRAGMENT_SHADER"));
+        assert!(ShaderProgram::has_stage(src, "COMPUTE_SHADER"));
+    }
+}
@@ -0,0 +1,216 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+use anyhow::Result;
+use crate::*;
+
+type MapFuture = Pin<Box<dyn Future<Output = std::result::Result<(), wgpu::BufferAsyncError>> + Send>>;
+
+struct Chunk{
+    buffer: wgpu::Buffer,
+    size: u64,
+    offset: u64,
+}
+
+///
+/// Statistics of a StagingPool.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StagingPoolStats{
+    /// Chunks that are mapped and receive writes.
+    pub active: usize,
+    /// Chunks that have been unmapped by finish and wait for recall.
+    pub closed: usize,
+    /// Chunks that are being mapped again.
+    pub mapping: usize,
+    /// Chunks that are mapped and unused.
+    pub free: usize,
+    /// Total size of all chunks in bytes.
+    pub size: u64,
+}
+
+///
+/// A pool of MAP_WRITE staging buffers used to upload data to other buffers.
+///
+/// The data is written directly into a mapped staging buffer and a copy to the destination is
+/// recorded into the encoder. After the encoder has been submitted the staging buffers are mapped
+/// again asynchronously and reused once mapping has completed.
+///
+/// Usage:
+/// - Write to buffers with write_buffer.
+/// - Call finish.
+/// - Submit the encoders used with write_buffer.
+/// - Call recall.
+///
+/// Chunks are never released on their own, call trim to drop unused chunks after a burst of
+/// uploads.
+///
+/// ```ignore
+/// let mut pool = StagingPool::new(1 << 16);
+///
+/// gpu.encode(|gpu, encoder|{
+///     pool.write_buffer(&gpu.device, encoder, &buffer, 0, &data).unwrap();
+///     pool.finish();
+/// });
+/// pool.recall();
+/// ```
+///
+pub struct StagingPool{
+    chunk_size: u64,
+    active: Vec<Chunk>,
+    closed: Vec<Chunk>,
+    mapping: Vec<(Chunk, MapFuture)>,
+    free: Vec<Chunk>,
+}
+
+impl StagingPool{
+    ///
+    /// Creates an empty pool.
+    /// chunk_size is the minimum size of a staging buffer in bytes.
+    ///
+    pub fn new(chunk_size: u64) -> Self{
+        Self{
+            chunk_size: chunk_size.max(wgpu::MAP_ALIGNMENT),
+            active: Vec::new(),
+            closed: Vec::new(),
+            mapping: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    ///
+    /// Writes data into a staging buffer and records a copy to dst at offset elements into the
+    /// encoder.
+    ///
+    /// Returns an error if dst does not have COPY_DST usage, the data does not fit, the copy is
+    /// not aligned to COPY_BUFFER_ALIGNMENT or a previous mapping of a staging buffer failed.
+    ///
+    pub fn write_buffer<C: bytemuck::Pod>(&mut self, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder, dst: &Buffer<C>, offset: usize, data: &[C]) -> Result<()>{
+        if !dst.usage().contains(wgpu::BufferUsages::COPY_DST){
+            return Err(anyhow::anyhow!("Destination buffer does not have COPY_DST usage"));
+        }
+        if offset.checked_add(data.len()).is_none_or(|end| end > dst.len()){
+            return Err(anyhow::anyhow!("Writing {} elements at offset {} into buffer of {} elements", data.len(), offset, dst.len()));
+        }
+
+        let data: &[u8] = bytemuck::cast_slice(data);
+        let size = data.len() as u64;
+        let dst_offset = (offset * std::mem::size_of::<C>()) as u64;
+        if !size.is_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT) || !dst_offset.is_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT){
+            return Err(anyhow::anyhow!("Copy of {} bytes to offset {} is not aligned to {} bytes", size, dst_offset, wgpu::COPY_BUFFER_ALIGNMENT));
+        }
+        if size == 0{
+            return Ok(());
+        }
+
+        self.poll_mapping()?;
+
+        let mut chunk = if let Some(i) = self.active.iter().position(|chunk| chunk.offset + size <= chunk.size){
+            self.active.swap_remove(i)
+        } else if let Some(i) = self.free.iter().position(|chunk| size <= chunk.size){
+            self.free.swap_remove(i)
+        } else{
+            let size = self.chunk_size.max(size).div_ceil(wgpu::MAP_ALIGNMENT) * wgpu::MAP_ALIGNMENT;
+            Chunk{
+                buffer: device.create_buffer(&wgpu::BufferDescriptor{
+                    label: Some("StagingPool chunk"),
+                    size,
+                    usage: wgpu::BufferUsages::MAP_WRITE | wgpu::BufferUsages::COPY_SRC,
+                    mapped_at_creation: true,
+                }),
+                size,
+                offset: 0,
+            }
+        };
+
+        chunk.buffer.slice(chunk.offset..chunk.offset + size)
+            .get_mapped_range_mut()
+            .copy_from_slice(data);
+        encoder.copy_buffer_to_buffer(&chunk.buffer, chunk.offset, &dst.buffer, dst_offset, size);
+
+        chunk.offset = (chunk.offset + size).div_ceil(wgpu::MAP_ALIGNMENT) * wgpu::MAP_ALIGNMENT;
+        self.active.push(chunk);
+        Ok(())
+    }
+
+    ///
+    /// Unmaps all chunks written to since the last call.
+    /// Has to be called before the encoders used with write_buffer are submitted.
+    ///
+    pub fn finish(&mut self){
+        for chunk in self.active.drain(..){
+            chunk.buffer.unmap();
+            self.closed.push(chunk);
+        }
+    }
+
+    ///
+    /// Starts mapping the closed chunks again so that they can be reused.
+    /// Has to be called after the encoders used with write_buffer have been submitted.
+    ///
+    /// Mapping completes when wgpu::Device::poll is called.
+    ///
+    pub fn recall(&mut self){
+        for chunk in self.closed.drain(..){
+            let future = chunk.buffer.slice(..).map_async(wgpu::MapMode::Write);
+            self.mapping.push((chunk, Box::pin(future)));
+        }
+    }
+
+    ///
+    /// Moves chunks whose mapping has completed to the free list.
+    /// Chunks that failed to map are dropped and the error is returned.
+    ///
+    pub fn poll_mapping(&mut self) -> Result<()>{
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut result = Ok(());
+
+        let mut i = 0;
+        while i < self.mapping.len(){
+            match self.mapping[i].1.as_mut().poll(&mut cx){
+                Poll::Ready(mapped) => {
+                    let (mut chunk, _) = self.mapping.swap_remove(i);
+                    match mapped{
+                        Ok(()) => {
+                            chunk.offset = 0;
+                            self.free.push(chunk);
+                        }
+                        Err(err) => result = Err(anyhow::anyhow!("Failed to map staging buffer: {}", err)),
+                    }
+                }
+                Poll::Pending => i += 1,
+            }
+        }
+        result
+    }
+
+    ///
+    /// Drops free chunks until the total size of the free chunks is at most max_free_size bytes.
+    /// Chunks that are in use or still mapping are kept.
+    ///
+    pub fn trim(&mut self, max_free_size: u64){
+        // Drop the largest chunks first.
+        self.free.sort_by_key(|chunk| chunk.size);
+        let mut free_size: u64 = self.free.iter().map(|chunk| chunk.size).sum();
+        while free_size > max_free_size{
+            match self.free.pop(){
+                Some(chunk) => free_size -= chunk.size,
+                None => break,
+            }
+        }
+    }
+
+    pub fn stats(&self) -> StagingPoolStats{
+        let chunks = self.active.iter()
+            .chain(self.closed.iter())
+            .chain(self.mapping.iter().map(|(chunk, _)| chunk))
+            .chain(self.free.iter());
+        StagingPoolStats{
+            active: self.active.len(),
+            closed: self.closed.len(),
+            mapping: self.mapping.len(),
+            free: self.free.len(),
+            size: chunks.map(|chunk| chunk.size).sum(),
+        }
+    }
+}
@@ -2,7 +2,11 @@ use image::GenericImageView;
 use crate::*;
 use crate::utils::RangeClamp;
 use std::fs;
-use std::ops::RangeBounds;
+use std::ops::{RangeBounds, Range};
+use std::marker::PhantomData;
+use std::num::NonZeroU32;
+use std::sync::Arc;
+use anyhow::{Result, ensure};
 
 pub trait IntoExtent3D{
     fn into_extent_3d(self) -> wgpu::Extent3d;
@@ -82,14 +86,21 @@ impl IntoExtent3D for wgpu::Extent3d{
 }
 
 ///
-/// 
+/// A wgpu::Texture with a default view and a sampler.
+///
+/// A Texture is not bound directly since the layout depends on its format and dimension.
+/// Bind a typed view created with the TextureViewBuilder returned by Texture::view instead.
 ///
 pub struct Texture{
     pub texture: wgpu::Texture,
-    //pub view: wgpu::TextureView,
-    pub sampler: wgpu::Sampler,
+    pub view: wgpu::TextureView,
+    pub sampler: Arc<wgpu::Sampler>,
     pub format: wgpu::TextureFormat,
+    pub dimension: wgpu::TextureDimension,
+    pub usage: wgpu::TextureUsages,
     pub size: wgpu::Extent3d,
+    pub mip_level_count: u32,
+    id: ResourceId,
 }
 
 pub struct TextureSlice<'ts>{
@@ -160,6 +171,7 @@ pub struct TextureBuilder<'tb>{
     pub usage: wgpu::TextureUsages,
     pub format: wgpu::TextureFormat,
     pub dimension: wgpu::TextureDimension,
+    pub mip_level_count: u32,
     pub label: wgpu::Label<'tb>,
 }
 
@@ -192,6 +204,7 @@ impl<'tb> Default for TextureBuilder<'tb>{
             usage,
             format,
             dimension,
+            mip_level_count: 1,
             label: None,
         }
     }
@@ -209,6 +222,16 @@ impl<'tb> TextureBuilder<'tb>{
         self
     }
 
+    ///
+    /// Sets the number of mip levels of the texture.
+    /// Only the first mip level is written by build.
+    ///
+    #[inline]
+    pub fn mip_level_count(mut self, mip_level_count: u32) -> Self{
+        self.mip_level_count = mip_level_count;
+        self
+    }
+
     #[inline]
     pub fn format(mut self, format: wgpu::TextureFormat) -> Self{
         self.format = format;
@@ -364,20 +387,18 @@ impl<'tb> TextureBuilder<'tb>{
             &wgpu::TextureDescriptor{
                 label: self.label,
                 size: self.size,
-                mip_level_count: 1,
+                mip_level_count: self.mip_level_count,
                 sample_count: 1,
-                dimension: wgpu::TextureDimension::D2,
+                dimension: self.dimension,
                 format: self.format,
                 usage: self.usage
             }
         );
-        /*
         let texture_view_desc = wgpu::TextureViewDescriptor{
             format: Some(self.format),
             ..Default::default()
         };
         let view = texture.create_view(&texture_view_desc);
-        */
         let sampler = device.create_sampler(
             &self.sampler_descriptor
         );
@@ -402,10 +423,14 @@ impl<'tb> TextureBuilder<'tb>{
 
         Texture{
             texture,
-            //view,
-            sampler,
+            view,
+            sampler: Arc::new(sampler),
             format: self.format,
+            dimension: self.dimension,
+            usage: self.usage,
             size: self.size,
+            mip_level_count: self.mip_level_count,
+            id: ResourceId::unique(),
         }
     }
 
@@ -414,9 +439,9 @@ impl<'tb> TextureBuilder<'tb>{
             &wgpu::TextureDescriptor{
                 label: self.label,
                 size: self.size,
-                mip_level_count: 1,
+                mip_level_count: self.mip_level_count,
                 sample_count: 1,
-                dimension: wgpu::TextureDimension::D2,
+                dimension: self.dimension,
                 format: self.format,
                 usage: self.usage
             }
@@ -432,21 +457,50 @@ impl<'tb> TextureBuilder<'tb>{
 
         Texture{
             texture,
-            //view,
-            sampler,
+            view,
+            sampler: Arc::new(sampler),
             format: self.format,
+            dimension: self.dimension,
+            usage: self.usage,
             size: self.size,
+            mip_level_count: self.mip_level_count,
+            id: ResourceId::unique(),
         }
     }
 
-    pub fn bound(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) -> Bound<Texture>{
-        self.build(device, queue).into_bound(device)
+    ///
+    /// Builds the texture and binds a view of it with the sample type K and the dimension D
+    /// together with its sampler.
+    ///
+    pub fn bound<K: kind::SampleKind, D: dim::ViewDimension>(&mut self, device: &std::sync::Arc<Device>, queue: &wgpu::Queue) -> Result<Bound<SampledTexture<K, D>>>{
+        Ok(self.build(device, queue).view().build_sampled()?.into_bound(device))
     }
 
 }
 
 impl Texture{
-    pub fn slice<S: RangeBounds<u32>>(&self, bound_x: S, bound_y: S, bound_z: S) -> TextureSlice{
+    ///
+    /// Returns the id of the underlying wgpu::Texture.
+    ///
+    #[inline]
+    pub fn id(&self) -> ResourceId{
+        self.id
+    }
+
+    ///
+    /// Returns a builder for a view of this texture.
+    ///
+    /// ```ignore
+    /// let view = texture.view()
+    ///     .mip_levels(0..1)
+    ///     .build::<kind::Float, dim::D2>()?;
+    /// ```
+    ///
+    pub fn view(&self) -> TextureViewBuilder<'_>{
+        TextureViewBuilder::new(self)
+    }
+
+    pub fn slice<S: RangeBounds<u32>>(&self, bound_x: S, bound_y: S, bound_z: S) -> TextureSlice<'_>{
         let range_x = bound_x.clamp(0..self.size.width);
         let range_y = bound_y.clamp(0..self.size.height);
         let range_z = bound_z.clamp(0..self.size.depth_or_array_layers);
@@ -470,74 +524,499 @@ impl Texture{
         }
     }
 }
-// TODO: decide on weather to use struct initialisation or function initialisation.
-impl BindGroupContent for Texture{
-    fn entries(visibility: Option<wgpu::ShaderStages>) -> Vec<binding::BindGroupLayoutEntry>{
-        vec!{
-            /*
-            BindGroupLayoutEntry{
-                visibility: visibility.unwrap_or(wgpu::ShaderStages::all()),
-                ty: binding::wgsl::texture_2d(),
-                count: None,
+///
+/// Marker types for the sample type of a TextureView.
+///
+pub mod kind{
+    ///
+    /// A trait implemented by the marker types of a TextureViews sample type.
+    ///
+    pub trait SampleKind{
+        const SAMPLE_TYPE: wgpu::TextureSampleType;
+        const SAMPLER_TYPE: wgpu::SamplerBindingType;
+    }
+
+    /// A filterable float texture sampled with a filtering sampler.
+    pub struct Float;
+    /// A float texture sampled with a non filtering sampler.
+    pub struct UnfilterableFloat;
+    /// A signed integer texture.
+    pub struct Sint;
+    /// An unsigned integer texture.
+    pub struct Uint;
+    /// A depth texture sampled with a comparison sampler.
+    pub struct Depth;
+
+    impl SampleKind for Float{
+        const SAMPLE_TYPE: wgpu::TextureSampleType = wgpu::TextureSampleType::Float{filterable: true};
+        const SAMPLER_TYPE: wgpu::SamplerBindingType = wgpu::SamplerBindingType::Filtering;
+    }
+    impl SampleKind for UnfilterableFloat{
+        const SAMPLE_TYPE: wgpu::TextureSampleType = wgpu::TextureSampleType::Float{filterable: false};
+        const SAMPLER_TYPE: wgpu::SamplerBindingType = wgpu::SamplerBindingType::NonFiltering;
+    }
+    impl SampleKind for Sint{
+        const SAMPLE_TYPE: wgpu::TextureSampleType = wgpu::TextureSampleType::Sint;
+        const SAMPLER_TYPE: wgpu::SamplerBindingType = wgpu::SamplerBindingType::NonFiltering;
+    }
+    impl SampleKind for Uint{
+        const SAMPLE_TYPE: wgpu::TextureSampleType = wgpu::TextureSampleType::Uint;
+        const SAMPLER_TYPE: wgpu::SamplerBindingType = wgpu::SamplerBindingType::NonFiltering;
+    }
+    impl SampleKind for Depth{
+        const SAMPLE_TYPE: wgpu::TextureSampleType = wgpu::TextureSampleType::Depth;
+        const SAMPLER_TYPE: wgpu::SamplerBindingType = wgpu::SamplerBindingType::Comparison;
+    }
+
+    ///
+    /// Returns true if a view of a format with the sample type format_sample_type can be bound
+    /// with the sample type sample_type.
+    ///
+    pub fn is_compatible(sample_type: wgpu::TextureSampleType, format_sample_type: wgpu::TextureSampleType) -> bool{
+        use wgpu::TextureSampleType::*;
+        matches!((sample_type, format_sample_type),
+            (Float{filterable: true}, Float{filterable: true})
+            | (Float{filterable: false}, Float{..})
+            | (Float{filterable: false}, Depth)
+            | (Sint, Sint) | (Uint, Uint) | (Depth, Depth)
+        )
+    }
+}
+
+///
+/// Marker types for the dimension of a TextureView.
+///
+pub mod dim{
+    ///
+    /// A trait implemented by the marker types of a TextureViews dimension.
+    ///
+    pub trait ViewDimension{
+        const DIMENSION: wgpu::TextureViewDimension;
+    }
+
+    pub struct D1;
+    pub struct D2;
+    pub struct D2Array;
+    pub struct Cube;
+    pub struct CubeArray;
+    pub struct D3;
+
+    impl ViewDimension for D1{
+        const DIMENSION: wgpu::TextureViewDimension = wgpu::TextureViewDimension::D1;
+    }
+    impl ViewDimension for D2{
+        const DIMENSION: wgpu::TextureViewDimension = wgpu::TextureViewDimension::D2;
+    }
+    impl ViewDimension for D2Array{
+        const DIMENSION: wgpu::TextureViewDimension = wgpu::TextureViewDimension::D2Array;
+    }
+    impl ViewDimension for Cube{
+        const DIMENSION: wgpu::TextureViewDimension = wgpu::TextureViewDimension::Cube;
+    }
+    impl ViewDimension for CubeArray{
+        const DIMENSION: wgpu::TextureViewDimension = wgpu::TextureViewDimension::CubeArray;
+    }
+    impl ViewDimension for D3{
+        const DIMENSION: wgpu::TextureViewDimension = wgpu::TextureViewDimension::D3;
+    }
+}
+
+///
+/// A builder for a TextureView of a Texture.
+///
+/// By default the view has the format of the texture and contains all mip levels and array
+/// layers.
+///
+pub struct TextureViewBuilder<'tvb>{
+    texture: &'tvb Texture,
+    label: wgpu::Label<'tvb>,
+    format: wgpu::TextureFormat,
+    aspect: wgpu::TextureAspect,
+    mip_levels: Range<u32>,
+    array_layers: Range<u32>,
+}
+
+impl<'tvb> TextureViewBuilder<'tvb>{
+    pub fn new(texture: &'tvb Texture) -> Self{
+        let array_layers = match texture.dimension{
+            wgpu::TextureDimension::D3 => 0..1,
+            _ => 0..texture.size.depth_or_array_layers,
+        };
+        Self{
+            texture,
+            label: None,
+            format: texture.format,
+            aspect: wgpu::TextureAspect::All,
+            mip_levels: 0..texture.mip_level_count,
+            array_layers,
+        }
+    }
+
+    pub fn label(mut self, label: wgpu::Label<'tvb>) -> Self{
+        self.label = label;
+        self
+    }
+
+    pub fn format(mut self, format: wgpu::TextureFormat) -> Self{
+        self.format = format;
+        self
+    }
+
+    pub fn aspect(mut self, aspect: wgpu::TextureAspect) -> Self{
+        self.aspect = aspect;
+        self
+    }
+
+    ///
+    /// Sets the mip levels of the view.
+    /// The range is clamped by the number of mip levels of the texture.
+    ///
+    pub fn mip_levels<S: RangeBounds<u32>>(mut self, mip_levels: S) -> Self{
+        self.mip_levels = mip_levels.clamp(0..self.texture.mip_level_count);
+        self
+    }
+
+    ///
+    /// Sets the array layers of the view.
+    /// The range is clamped by the number of array layers of the texture.
+    ///
+    pub fn array_layers<S: RangeBounds<u32>>(mut self, array_layers: S) -> Self{
+        self.array_layers = array_layers.clamp(0..self.texture.size.depth_or_array_layers);
+        self
+    }
+
+    ///
+    /// Returns the sample type of the view's format with respect to its aspect.
+    ///
+    fn sample_type(&self) -> wgpu::TextureSampleType{
+        match self.aspect{
+            wgpu::TextureAspect::StencilOnly => wgpu::TextureSampleType::Uint,
+            _ => self.format.describe().sample_type,
+        }
+    }
+
+    ///
+    /// Checks that the selected array layers of the texture can be viewed with the dimension.
+    ///
+    fn check_dimension(&self, dimension: wgpu::TextureViewDimension) -> Result<()>{
+        let layers = self.array_layers.end - self.array_layers.start;
+        match (self.texture.dimension, dimension){
+            (wgpu::TextureDimension::D1, wgpu::TextureViewDimension::D1) => {},
+            (wgpu::TextureDimension::D2, wgpu::TextureViewDimension::D2) => {
+                ensure!(layers == 1, "A D2 view requires exactly one array layer but has {}", layers);
+            },
+            (wgpu::TextureDimension::D2, wgpu::TextureViewDimension::D2Array) => {},
+            (wgpu::TextureDimension::D2, wgpu::TextureViewDimension::Cube) => {
+                ensure!(layers == 6, "A Cube view requires exactly six array layers but has {}", layers);
+            },
+            (wgpu::TextureDimension::D2, wgpu::TextureViewDimension::CubeArray) => {
+                ensure!(layers.is_multiple_of(6), "A CubeArray view requires a multiple of six array layers but has {}", layers);
+            },
+            (wgpu::TextureDimension::D3, wgpu::TextureViewDimension::D3) => {},
+            (texture_dimension, view_dimension) => {
+                anyhow::bail!("A texture of dimension {:?} cannot be viewed as {:?}", texture_dimension, view_dimension);
+            },
+        }
+
+        Ok(())
+    }
+
+    fn create_view(&self, dimension: wgpu::TextureViewDimension) -> wgpu::TextureView{
+        self.texture.texture.create_view(&wgpu::TextureViewDescriptor{
+            label: self.label,
+            format: Some(self.format),
+            dimension: Some(dimension),
+            aspect: self.aspect,
+            base_mip_level: self.mip_levels.start,
+            mip_level_count: NonZeroU32::new(self.mip_levels.end - self.mip_levels.start),
+            base_array_layer: self.array_layers.start,
+            array_layer_count: NonZeroU32::new(self.array_layers.end - self.array_layers.start),
+        })
+    }
+
+    ///
+    /// Creates the view.
+    /// Returns an error if the format of the view cannot be sampled as K or the texture cannot
+    /// be viewed with the dimension D.
+    ///
+    pub fn build<K: kind::SampleKind, D: dim::ViewDimension>(self) -> Result<TextureView<K, D>>{
+        ensure!(kind::is_compatible(K::SAMPLE_TYPE, self.sample_type()),
+            "A view of format {:?} cannot be sampled as {:?}", self.format, K::SAMPLE_TYPE);
+        ensure!(!self.mip_levels.is_empty(), "A TextureView requires at least one mip level");
+        ensure!(!self.array_layers.is_empty(), "A TextureView requires at least one array layer");
+
+        self.check_dimension(D::DIMENSION)?;
+
+        Ok(TextureView{
+            view: self.create_view(D::DIMENSION),
+            format: self.format,
+            id: ResourceId::unique(),
+            _ty: PhantomData,
+        })
+    }
+
+    ///
+    /// Creates a view that is bound as a storage texture with the format F and the access A.
+    /// Returns an error if the format of the view is not F, the texture was not created with
+    /// STORAGE_BINDING usage or more than one mip level is selected.
+    ///
+    pub fn build_storage<F: format::StorageFormat, A: access::StorageAccess, D: dim::ViewDimension>(self) -> Result<StorageTexture<F, A, D>>{
+        ensure!(self.format == F::FORMAT,
+            "A view of format {:?} cannot be bound as a storage texture of format {:?}", self.format, F::FORMAT);
+        ensure!(self.texture.usage.contains(wgpu::TextureUsages::STORAGE_BINDING),
+            "A storage texture requires a texture with STORAGE_BINDING usage");
+        ensure!(self.mip_levels.end - self.mip_levels.start == 1,
+            "A storage texture requires exactly one mip level");
+        ensure!(!self.array_layers.is_empty(), "A TextureView requires at least one array layer");
+
+        self.check_dimension(D::DIMENSION)?;
+
+        let mip_level = self.mip_levels.start;
+        let size = wgpu::Extent3d{
+            width: (self.texture.size.width >> mip_level).max(1),
+            height: (self.texture.size.height >> mip_level).max(1),
+            depth_or_array_layers: match self.texture.dimension{
+                wgpu::TextureDimension::D3 => (self.texture.size.depth_or_array_layers >> mip_level).max(1),
+                _ => self.array_layers.end - self.array_layers.start,
             },
-            */
+        };
+
+        Ok(StorageTexture{
+            view: self.create_view(D::DIMENSION),
+            size,
+            id: ResourceId::unique(),
+            _ty: PhantomData,
+        })
+    }
+
+    ///
+    /// Creates the view and pairs it with the sampler of the texture.
+    ///
+    pub fn build_sampled<K: kind::SampleKind, D: dim::ViewDimension>(self) -> Result<SampledTexture<K, D>>{
+        let sampler = self.texture.sampler.clone();
+        Ok(SampledTexture{
+            view: self.build()?,
+            sampler,
+        })
+    }
+}
+
+///
+/// A view of a texture with the sample type K and the dimension D.
+/// It is bound as a single texture entry.
+///
+#[derive(DerefMut)]
+pub struct TextureView<K: kind::SampleKind = kind::Float, D: dim::ViewDimension = dim::D2>{
+    #[target]
+    pub view: wgpu::TextureView,
+    pub format: wgpu::TextureFormat,
+    id: ResourceId,
+    _ty: PhantomData<(K, D)>,
+}
+
+impl<K: kind::SampleKind, D: dim::ViewDimension> BindGroupContent for TextureView<K, D>{
+    const BINDING_COUNT: u32 = 1;
+
+    fn entries(visibility: Option<wgpu::ShaderStages>) -> Vec<BindGroupLayoutEntry> {
+        vec![
             BindGroupLayoutEntry{
                 visibility: visibility.unwrap_or(wgpu::ShaderStages::all()),
-                ty: binding::wgsl::sampler(),
+                ty: wgpu::BindingType::Texture {
+                    sample_type: K::SAMPLE_TYPE,
+                    view_dimension: D::DIMENSION,
+                    multisampled: false,
+                },
                 count: None,
+                binding: None,
             }
+        ]
+    }
+
+    fn resources(&self) -> Vec<BindingResource<'_>> {
+        vec![
+            wgpu::BindingResource::TextureView(&self.view).into(),
+        ]
+    }
+
+    fn resource_ids(&self) -> Vec<ResourceId> {
+        vec![
+            self.id,
+        ]
+    }
+}
+
+///
+/// A TextureView together with a sampler.
+/// It is bound as a texture entry followed by a sampler entry whose type is derived from K.
+///
+pub struct SampledTexture<K: kind::SampleKind = kind::Float, D: dim::ViewDimension = dim::D2>{
+    pub view: TextureView<K, D>,
+    pub sampler: Arc<wgpu::Sampler>,
+}
+
+impl<K: kind::SampleKind, D: dim::ViewDimension> SampledTexture<K, D>{
+    pub fn new(view: TextureView<K, D>, sampler: Arc<wgpu::Sampler>) -> Self{
+        Self{
+            view,
+            sampler,
         }
     }
+}
+
+impl<K: kind::SampleKind, D: dim::ViewDimension> BindGroupContent for SampledTexture<K, D>{
+    const BINDING_COUNT: u32 = 2;
+
+    fn entries(visibility: Option<wgpu::ShaderStages>) -> Vec<BindGroupLayoutEntry> {
+        let mut ret = TextureView::<K, D>::entries(visibility);
+        ret.push(BindGroupLayoutEntry{
+            visibility: visibility.unwrap_or(wgpu::ShaderStages::all()),
+            ty: wgpu::BindingType::Sampler(K::SAMPLER_TYPE),
+            count: None,
+            binding: None,
+        });
+        ret
+    }
+
+    fn resources(&self) -> Vec<BindingResource<'_>> {
+        let mut ret = self.view.resources();
+        ret.push(wgpu::BindingResource::Sampler(&self.sampler).into());
+        ret
+    }
 
-    fn resources(&self) -> Vec<wgpu::BindingResource> {
-        vec!{
-            //wgpu::BindingResource::TextureView(&self.view),
-            wgpu::BindingResource::Sampler(&self.sampler),
+    fn resource_ids(&self) -> Vec<ResourceId> {
+        self.view.resource_ids()
+    }
+}
+
+///
+/// Marker types for the format of a StorageTexture.
+///
+pub mod format{
+    ///
+    /// A trait implemented by the marker types of the formats that can be used as storage
+    /// textures.
+    ///
+    pub trait StorageFormat{
+        const FORMAT: wgpu::TextureFormat;
+    }
+
+    macro_rules! storage_formats{
+        ($($name:ident)+) => {
+            $(
+                pub struct $name;
+
+                impl StorageFormat for $name{
+                    const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::$name;
+                }
+            )+
         }
     }
+
+    storage_formats!{
+        Rgba8Unorm Rgba8Snorm Rgba8Uint Rgba8Sint
+        Rgba16Uint Rgba16Sint Rgba16Float
+        R32Uint R32Sint R32Float
+        Rg32Uint Rg32Sint Rg32Float
+        Rgba32Uint Rgba32Sint Rgba32Float
+    }
+}
+
+///
+/// Marker types for the access of a StorageTexture.
+///
+pub mod access{
+    ///
+    /// A trait implemented by the marker types of a StorageTextures access.
+    ///
+    pub trait StorageAccess{
+        const ACCESS: wgpu::StorageTextureAccess;
+    }
+
+    pub struct ReadOnly;
+    pub struct WriteOnly;
+    /// Requires the TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES feature for most formats.
+    pub struct ReadWrite;
+
+    impl StorageAccess for ReadOnly{
+        const ACCESS: wgpu::StorageTextureAccess = wgpu::StorageTextureAccess::ReadOnly;
+    }
+    impl StorageAccess for WriteOnly{
+        const ACCESS: wgpu::StorageTextureAccess = wgpu::StorageTextureAccess::WriteOnly;
+    }
+    impl StorageAccess for ReadWrite{
+        const ACCESS: wgpu::StorageTextureAccess = wgpu::StorageTextureAccess::ReadWrite;
+    }
 }
 
+///
+/// A view of a texture that is bound as storage texture with the format F and the access A.
+///
+/// ```ignore
+/// let texture = TextureBuilder::new()
+///     .clear([512, 512])
+///     .format(wgpu::TextureFormat::Rgba8Unorm)
+///     .usage(wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::COPY_SRC)
+///     .build_empty(&gpu.device);
+/// let image = texture.view()
+///     .build_storage::<format::Rgba8Unorm, access::WriteOnly, dim::D2>()?
+///     .into_bound(&gpu.device);
+///
+/// let mut cpass_ppl = cpass.set_pipeline(&pipeline);
+/// cpass_ppl.set_bind_group(0, &image, &[]);
+/// cpass_ppl.dispatch_texture(&image)?;
+/// ```
+///
 #[derive(DerefMut)]
-pub struct TextureView{
+pub struct StorageTexture<F: format::StorageFormat, A: access::StorageAccess = access::WriteOnly, D: dim::ViewDimension = dim::D2>{
     #[target]
     pub view: wgpu::TextureView,
-    dimension: wgpu::TextureViewDimension,
+    pub size: wgpu::Extent3d,
+    id: ResourceId,
+    _ty: PhantomData<(F, A, D)>,
 }
 
-impl BindGroupContent for TextureView{
-    // TODO: pass down sampler.
+impl<F: format::StorageFormat, A: access::StorageAccess, D: dim::ViewDimension> BindGroupContent for StorageTexture<F, A, D>{
+    const BINDING_COUNT: u32 = 1;
+
     fn entries(visibility: Option<wgpu::ShaderStages>) -> Vec<BindGroupLayoutEntry> {
         vec![
             BindGroupLayoutEntry{
                 visibility: visibility.unwrap_or(wgpu::ShaderStages::all()),
-                ty: wgpu::BindingType::Texture {
-                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
-                    view_dimension: wgpu::TextureViewDimension::D2,
-                    multisampled: false,
+                ty: wgpu::BindingType::StorageTexture{
+                    access: A::ACCESS,
+                    format: F::FORMAT,
+                    view_dimension: D::DIMENSION,
                 },
                 count: None,
+                binding: None,
             }
         ]
     }
 
-    fn resources(&self) -> Vec<wgpu::BindingResource> {
+    fn resources(&self) -> Vec<BindingResource<'_>> {
+        vec![
+            wgpu::BindingResource::TextureView(&self.view).into(),
+        ]
+    }
+
+    fn resource_ids(&self) -> Vec<ResourceId> {
         vec![
-            wgpu::BindingResource::TextureView(&self.view),
+            self.id,
         ]
     }
 }
 
 #[cfg(feature = "imgui")]
 impl ColorAttachment for imgui_wgpu::Texture{
-    fn color_attachment_clear(&self) -> wgpu::RenderPassColorAttachment {
+    fn color_attachment_clear(&self) -> wgpu::RenderPassColorAttachment<'_> {
         self.view().color_attachment_clear()
     }
 
-    fn color_attachment_clear_with(&self, color: wgpu::Color) -> wgpu::RenderPassColorAttachment {
+    fn color_attachment_clear_with(&self, color: wgpu::Color) -> wgpu::RenderPassColorAttachment<'_> {
         self.view().color_attachment_clear_with(color)
     }
 
-    fn color_attachment_load(&self) -> wgpu::RenderPassColorAttachment {
+    fn color_attachment_load(&self) -> wgpu::RenderPassColorAttachment<'_> {
         self.view().color_attachment_load()
     }
 }
@@ -2,6 +2,8 @@ use super::binding::Bound;
 use super::binding::CreateBindGroupLayout;
 use super::buffer::*;
 use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+use crate::Device;
 use super::binding;
 use super::binding::BindGroupContent;
 
@@ -108,22 +110,41 @@ impl<C: bytemuck::Pod> UniformVec<C>{
     pub fn len(&self) -> usize{
         self.content.len()
     }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool{
+        self.content.is_empty()
+    }
 }
 
 impl<C: bytemuck::Pod> binding::BindGroupContent for UniformVec<C>{
+    const BINDING_COUNT: u32 = 1;
+
     fn entries(visibility: Option<wgpu::ShaderStages>) -> Vec<binding::BindGroupLayoutEntry>{
         vec!{
             binding::BindGroupLayoutEntry::new(visibility.unwrap_or(wgpu::ShaderStages::all()), binding::wgsl::uniform()),
         }
     }
 
-    fn resources(& self) -> Vec<wgpu::BindingResource> {
+    fn resources(& self) -> Vec<binding::BindingResource<'_>> {
+        vec!{
+            self.buffer.as_entire_binding().into(),
+        }
+    }
+
+    fn resource_ids(&self) -> Vec<binding::ResourceId> {
         vec!{
-            self.buffer.as_entire_binding(),
+            self.buffer.id(),
         }
     }
 }
 
+impl<C: bytemuck::Pod> binding::BindingSize for UniformVec<C>{
+    fn binding_size() -> u64 {
+        std::mem::size_of::<C>() as u64
+    }
+}
+
 ///
 /// A UniformVec with a single element usefull for cameras etc.
 ///
@@ -147,17 +168,29 @@ impl<C: bytemuck::Pod> Uniform<C>{
 }
 
 impl<C: bytemuck::Pod> BindGroupContent for Uniform<C>{
+    const BINDING_COUNT: u32 = 1;
+
     fn entries(visibility: Option<wgpu::ShaderStages>) -> Vec<binding::BindGroupLayoutEntry>{
         vec!{
             binding::BindGroupLayoutEntry::new(visibility.unwrap_or(wgpu::ShaderStages::all()), binding::wgsl::uniform())
         }
     }
 
-    fn resources(&self) -> Vec<wgpu::BindingResource> {
+    fn resources(&self) -> Vec<binding::BindingResource<'_>> {
         vec!{
-            self.uniform_vec.buffer.as_entire_binding(),
+            self.uniform_vec.buffer.as_entire_binding().into(),
         }
     }
+
+    fn resource_ids(&self) -> Vec<binding::ResourceId> {
+        self.uniform_vec.resource_ids()
+    }
+}
+
+impl<C: bytemuck::Pod> binding::BindingSize for Uniform<C>{
+    fn binding_size() -> u64 {
+        std::mem::size_of::<C>() as u64
+    }
 }
 
 ///
@@ -169,13 +202,17 @@ pub struct BoundUniform<C: bytemuck::Pod>{
 }
 
 impl <C: bytemuck::Pod> BoundUniform<C>{
-    pub fn new(device: &wgpu::Device, src: C) -> Self{
+    pub fn new(device: &Arc<Device>, src: C) -> Self{
         Self{
             bind_group: Uniform::new(src, device).into_bound(device)
         }
     }
 }
 
+impl<C: bytemuck::Pod> binding::SlotContent for BoundUniform<C>{
+    type Content = Uniform<C>;
+}
+
 impl<C: bytemuck::Pod> binding::GetBindGroup for BoundUniform<C>{
     fn bind_group(&self) -> &wgpu::BindGroup {
         self.bind_group.bind_group()
@@ -183,7 +220,7 @@ impl<C: bytemuck::Pod> binding::GetBindGroup for BoundUniform<C>{
 }
 
 impl<C: bytemuck::Pod> CreateBindGroupLayout for BoundUniform<C>{
-    fn create_bind_group_layout(device: &wgpu::Device, label: Option<&str>) -> crate::BindGroupLayoutWithDesc {
+    fn create_bind_group_layout(device: &crate::Device, label: Option<&str>) -> crate::BindGroupLayoutWithDesc {
         Bound::<Uniform<C>>::create_bind_group_layout(device, label)
     }
 }
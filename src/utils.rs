@@ -36,21 +36,6 @@ macro_rules! align_macro{
 }
 align_macro!(i8 u8 i16 u16 i32 u32 i64 u64 i128 u128);
 
-#[cfg(test)]
-mod test{
-    use super::*;
-    #[test]
-    fn test_align(){
-        let i: i32 = 5;
-        let c = i.align_ceil(4);
-        let f = i.align_floor(4);
-        println!("{}", c);
-
-        assert_eq!(c, 8);
-        assert_eq!(f, 4);
-    }
-}
-
 ///
 /// A trait implemented on RangeBounds to clamp them inside another range.
 ///
@@ -87,3 +72,18 @@ impl<T: PrimInt, B: RangeBounds<T>> RangeClamp<T> for B{
         start_bound..end_bound
     }
 }
+
+#[cfg(test)]
+mod test{
+    use super::*;
+    #[test]
+    fn test_align(){
+        let i: i32 = 5;
+        let c = i.align_ceil(4);
+        let f = i.align_floor(4);
+        println!("{}", c);
+
+        assert_eq!(c, 8);
+        assert_eq!(f, 4);
+    }
+}
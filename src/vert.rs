@@ -98,9 +98,9 @@ mod tests {
     // TODO: write the tests.
     #[test]
     fn test_vert8_buffer_layout(){
-        let layout = VertTest8::buffer_layout();
+        let _layout = VertTest8::buffer_layout();
 
-        let layout_cmp = wgpu::VertexBufferLayout{
+        let _layout_cmp = wgpu::VertexBufferLayout{
             array_stride: 24,
             step_mode: wgpu::VertexStepMode::Vertex,
             attributes: &[
@@ -110,11 +110,11 @@ mod tests {
 
     #[test]
     fn test_vert16_buffer_layout(){
-        let layout = VertTest16::buffer_layout();
+        let _layout = VertTest16::buffer_layout();
     }
 
     #[test]
     fn test_vert32_buffer_layout(){
-        let layout = VertTest32::buffer_layout();
+        let _layout = VertTest32::buffer_layout();
     }
 }
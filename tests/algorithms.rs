@@ -0,0 +1,166 @@
+use ewgpu::*;
+use ewgpu::algorithms::*;
+
+fn software_gpu() -> GPUContext{
+    GPUContextBuilder::new()
+        .set_features_util()
+        .set_force_fallback_addapter(true)
+        .set_limits(wgpu::Limits{
+            max_push_constant_size: 128,
+            ..Default::default()
+        })
+        .build()
+}
+
+///
+/// Deterministic pseudo random numbers so that failures are reproducible.
+///
+fn random_u32(len: usize, seed: u32) -> Vec<u32>{
+    let mut state = seed;
+    (0..len).map(|_|{
+        state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+        state
+    }).collect()
+}
+
+fn input<C: bytemuck::Pod>(gpu: &GPUContext, data: &[C]) -> Buffer<C>{
+    BufferBuilder::new()
+        .storage().write()
+        .build(&gpu.device, data)
+}
+
+fn output<C: bytemuck::Pod>(gpu: &GPUContext, len: usize) -> Buffer<C>{
+    BufferBuilder::new()
+        .storage().read()
+        .build_empty(&gpu.device, len)
+}
+
+#[test]
+fn reduce(){
+    let mut gpu = software_gpu();
+
+    let sum = Reduce::<u32>::sum(&gpu.device).unwrap();
+    let min = Reduce::<i32>::min(&gpu.device).unwrap();
+    let max = Reduce::<f32>::max(&gpu.device).unwrap();
+
+    for len in [1, 255, 256, 1000, 70_000]{
+        let data: Vec<u32> = random_u32(len, 1).iter().map(|x| x % 1000).collect();
+        let signed: Vec<i32> = data.iter().map(|&x| x as i32 - 500).collect();
+        let floats: Vec<f32> = data.iter().map(|&x| x as f32 * 0.5).collect();
+
+        let data_buf = input(&gpu, &data);
+        let signed_buf = input(&gpu, &signed);
+        let floats_buf = input(&gpu, &floats);
+        let sum_buf = output::<u32>(&gpu, 1);
+        let min_buf = output::<i32>(&gpu, 1);
+        let max_buf = output::<f32>(&gpu, 1);
+
+        gpu.encode(|gpu, encoder|{
+            sum.encode(&gpu.device, encoder, &data_buf, &sum_buf).unwrap();
+            min.encode(&gpu.device, encoder, &signed_buf, &min_buf).unwrap();
+            max.encode(&gpu.device, encoder, &floats_buf, &max_buf).unwrap();
+        });
+
+        assert_eq!(sum_buf.slice(..).map_blocking(&gpu.device)[0], data.iter().sum::<u32>());
+        assert_eq!(min_buf.slice(..).map_blocking(&gpu.device)[0], signed.iter().copied().min().unwrap_or(i32::MAX));
+        assert_eq!(max_buf.slice(..).map_blocking(&gpu.device)[0], floats.iter().copied().fold(f32::NEG_INFINITY, f32::max));
+    }
+}
+
+#[test]
+fn prefix_sum(){
+    let mut gpu = software_gpu();
+
+    let scan = PrefixSum::<u32>::new(&gpu.device).unwrap();
+
+    for len in [1, 256, 257, 1000, 70_000]{
+        let data: Vec<u32> = random_u32(len, 2).iter().map(|x| x % 100).collect();
+
+        let data_buf = input(&gpu, &data);
+        let inclusive_buf = output::<u32>(&gpu, len);
+        let exclusive_buf = output::<u32>(&gpu, len);
+
+        gpu.encode(|gpu, encoder|{
+            scan.encode_inclusive(&gpu.device, encoder, &data_buf, &inclusive_buf).unwrap();
+            scan.encode_exclusive(&gpu.device, encoder, &data_buf, &exclusive_buf).unwrap();
+        });
+
+        let inclusive: Vec<u32> = data.iter()
+            .scan(0, |acc, x|{
+                *acc += x;
+                Some(*acc)
+            })
+            .collect();
+        let exclusive: Vec<u32> = inclusive.iter().zip(&data).map(|(s, x)| s - x).collect();
+
+        assert_eq!(inclusive_buf.slice(..).map_blocking(&gpu.device).as_ref(), &inclusive[..]);
+        assert_eq!(exclusive_buf.slice(..).map_blocking(&gpu.device).as_ref(), &exclusive[..]);
+    }
+}
+
+#[test]
+fn radix_sort(){
+    let mut gpu = software_gpu();
+
+    let sort = RadixSort::new(&gpu.device).unwrap();
+
+    for len in [1, 300, 5000]{
+        let keys = random_u32(len, 3);
+        let values: Vec<u32> = (0..len as u32).collect();
+
+        let keys_only = BufferBuilder::new()
+            .storage().read()
+            .build(&gpu.device, &keys);
+        let pair_keys = BufferBuilder::new()
+            .storage().read()
+            .build(&gpu.device, &keys);
+        let pair_values = BufferBuilder::new()
+            .storage().read()
+            .build(&gpu.device, &values);
+
+        gpu.encode(|gpu, encoder|{
+            sort.encode_keys(&gpu.device, encoder, &keys_only).unwrap();
+            sort.encode_pairs(&gpu.device, encoder, &pair_keys, &pair_values).unwrap();
+        });
+
+        let mut pairs: Vec<(u32, u32)> = keys.iter().copied().zip(values.iter().copied()).collect();
+        // Stable sort like the radix sort.
+        pairs.sort_by_key(|&(key, _)| key);
+        let sorted_keys: Vec<u32> = pairs.iter().map(|&(key, _)| key).collect();
+        let sorted_values: Vec<u32> = pairs.iter().map(|&(_, value)| value).collect();
+
+        assert_eq!(keys_only.slice(..).map_blocking(&gpu.device).as_ref(), &sorted_keys[..]);
+        assert_eq!(pair_keys.slice(..).map_blocking(&gpu.device).as_ref(), &sorted_keys[..]);
+        assert_eq!(pair_values.slice(..).map_blocking(&gpu.device).as_ref(), &sorted_values[..]);
+    }
+}
+
+#[test]
+fn compact(){
+    let mut gpu = software_gpu();
+
+    let compact = Compact::<[f32; 2]>::new(&gpu.device).unwrap();
+    assert!(Compact::<u8>::new(&gpu.device).is_err());
+
+    for len in [1, 1000, 70_000]{
+        let data: Vec<[f32; 2]> = (0..len).map(|i| [i as f32, -(i as f32)]).collect();
+        let flags: Vec<u32> = random_u32(len, 4).iter().map(|x| x % 3).collect();
+
+        let data_buf = input(&gpu, &data);
+        let flags_buf = input(&gpu, &flags);
+        let out_buf = output::<[f32; 2]>(&gpu, len);
+        let count_buf = output::<u32>(&gpu, 1);
+
+        gpu.encode(|gpu, encoder|{
+            compact.encode(&gpu.device, encoder, &data_buf, &flags_buf, &out_buf, &count_buf).unwrap();
+        });
+
+        let kept: Vec<[f32; 2]> = data.iter().zip(&flags)
+            .filter(|(_, &flag)| flag != 0)
+            .map(|(&x, _)| x)
+            .collect();
+
+        assert_eq!(count_buf.slice(..).map_blocking(&gpu.device)[0] as usize, kept.len());
+        assert_eq!(&out_buf.slice(..).map_blocking(&gpu.device)[..kept.len()], &kept[..]);
+    }
+}
@@ -0,0 +1,131 @@
+use ewgpu::*;
+
+#[test]
+fn bound_recreated_on_resize(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let mut buffer = BufferBuilder::<u32>::new()
+        .storage().read()
+        .build_empty(&gpu.device, 1)
+        .into_bound(&gpu.device);
+
+    let id = buffer.id();
+    let bind_group: *const wgpu::BindGroup = buffer.bind_group();
+    assert_eq!(bind_group, buffer.bind_group() as *const _);
+
+    buffer.update();
+    assert_eq!(bind_group, buffer.bind_group() as *const _);
+
+    // The BindGroup is recreated when it is requested without calling update.
+    buffer.resize_clear(4, &gpu.device);
+
+    assert_ne!(id, buffer.id());
+    assert_ne!(bind_group, buffer.bind_group() as *const _);
+}
+
+#[test]
+fn bind_group_layouts_cached(){
+    use std::sync::Arc;
+
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let a = BufferBuilder::<u32>::new()
+        .storage()
+        .build_empty(&gpu.device, 1)
+        .into_bound(&gpu.device);
+    let b = BufferBuilder::<u32>::new()
+        .storage()
+        .build_empty(&gpu.device, 2)
+        .into_bound(&gpu.device);
+
+    assert!(Arc::ptr_eq(&a.bind_group_layout().layout, &b.bind_group_layout().layout));
+
+    let layout = Buffer::<u32>::create_bind_group_layout(&gpu.device, None);
+    assert!(Arc::ptr_eq(&a.bind_group_layout().layout, &layout.layout));
+    assert_eq!(gpu.device.bind_group_layout_count(), 1);
+}
+
+#[test]
+fn binding_arrays_require_features(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_default()
+        .build();
+
+    let buffers = [
+        BufferBuilder::<u32>::new().storage().build_empty(&gpu.device, 1),
+        BufferBuilder::<u32>::new().storage().build_empty(&gpu.device, 1),
+    ];
+    assert!(BufferArray::new(&gpu.device, buffers).is_err());
+
+    let entries = BufferArray::<u32, 2>::entries(None);
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].count, std::num::NonZeroU32::new(2));
+}
+
+#[derive(BindGroupContent)]
+struct DynamicParams{
+    #[uniform]
+    #[dynamic_offset]
+    params: Buffer<[u32; 64]>,
+    output: Buffer<u32>,
+}
+
+#[test]
+fn bind_with_dynamic_offset(){
+    let mut gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let cshader = ComputeShader::from_src(&gpu.device, "
+        #version 460
+        #if COMPUTE_SHADER
+
+        layout(set = 0, binding = 0) uniform Params{
+            uint value;
+        };
+        layout(set = 0, binding = 1) buffer Output{
+            uint out_buf[];
+        };
+
+        void main(){
+            out_buf[0] = value;
+        }
+        #endif
+        ", None).unwrap();
+
+    let mut params = [[0; 64]; 2];
+    params[0][0] = 3;
+    params[1][0] = 7;
+
+    let content = DynamicParams{
+        params: BufferBuilder::new()
+            .uniform()
+            .build(&gpu.device, &params),
+        output: BufferBuilder::new()
+            .storage().read()
+            .build_empty(&gpu.device, 1),
+    }.into_bound(&gpu.device);
+
+    let layout = PipelineLayoutBuilder::new()
+        .push_bind_group(&DynamicParams::create_bind_group_layout(&gpu.device, None))
+        .build(&gpu.device, None);
+
+    let cpipeline = ComputePipelineBuilder::new(&cshader)
+        .set_layout(&layout)
+        .build(&gpu.device);
+
+    gpu.encode(|_, encoder|{
+        let mut cpass = ComputePass::new(encoder, None);
+        let mut cpass_ppl = cpass.set_pipeline(&cpipeline);
+
+        // Without limiting the binding to one element this offset would exceed the buffer.
+        cpass_ppl.set_bind_group(0, &content, &[256]);
+        cpass_ppl.dispatch(1, 1, 1);
+    });
+
+    assert_eq!(content.output.slice(..).map_blocking(&gpu.device).as_ref(), [7]);
+}
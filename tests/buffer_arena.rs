@@ -0,0 +1,48 @@
+use ewgpu::*;
+
+#[test]
+fn arena_alloc_write_free(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let mut arena = BufferArena::<u32>::new(
+        &gpu.device,
+        wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        64,
+        Some("arena")
+    );
+    assert_eq!(arena.align(), 2);
+
+    let a = arena.alloc(&gpu.device, 3);
+    let b = arena.alloc(&gpu.device, 16);
+    let large = arena.alloc(&gpu.device, 100);
+
+    assert_eq!(a.offset(), 0);
+    assert_eq!(b.offset(), 4);
+    assert_eq!(arena.stats().blocks, 2);
+
+    arena.write(&gpu.queue, &a, &[1, 2, 3]);
+    arena.write(&gpu.queue, &b, &[7; 16]);
+    gpu.queue.submit(None);
+
+    assert_eq!(arena.slice(&a).map_blocking(&gpu.device).as_ref(), [1, 2, 3]);
+    assert_eq!(arena.slice(&b).map_blocking(&gpu.device).as_ref(), [7; 16]);
+
+    arena.free(a);
+    let stats = arena.stats();
+    assert_eq!(stats.allocations, 2);
+    assert_eq!(stats.capacity, 164);
+    assert_eq!(stats.allocated, 116);
+    assert!(stats.fragmentation > 0.);
+
+    let c = arena.alloc(&gpu.device, 4);
+    assert_eq!(c.offset(), 0);
+
+    arena.free(b);
+    arena.free(c);
+    arena.free(large);
+    let stats = arena.stats();
+    assert_eq!(stats.allocated, 0);
+    assert_eq!(stats.fragmentation, 0.);
+}
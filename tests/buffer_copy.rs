@@ -0,0 +1,85 @@
+use ewgpu::*;
+
+#[test]
+fn copy_to_buffer_checked(){
+    let mut gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let src = BufferBuilder::<u32>::new()
+        .copy_src()
+        .build(&gpu.device, &[1, 2, 3, 4]);
+    let mut dst = BufferBuilder::<u32>::new()
+        .read()
+        .copy_dst()
+        .build(&gpu.device, &[0, 0, 0, 0]);
+    let mut no_copy_dst = BufferBuilder::<u32>::new()
+        .read()
+        .build(&gpu.device, &[0, 0, 0, 0]);
+    let mut dst_f32 = BufferBuilder::<f32>::new()
+        .read()
+        .copy_dst()
+        .build(&gpu.device, &[0., 0.]);
+
+    gpu.encode(|_, encoder|{
+        assert!(src.slice(..).copy_to_buffer(&mut dst, 1, encoder).is_err());
+        assert!(src.slice(..).copy_to_buffer(&mut dst, 5, encoder).is_err());
+        assert!(src.slice(..).copy_to_buffer(&mut dst, u64::MAX, encoder).is_err());
+        assert!(src.slice(..).copy_to_buffer(&mut no_copy_dst, 0, encoder).is_err());
+        assert!(dst.slice(..).copy_to_buffer(&mut no_copy_dst, 0, encoder).is_err());
+
+        src.slice(1..3).copy_to_buffer(&mut dst, 2, encoder).unwrap();
+        src.slice(2..).copy_to_buffer_as(&mut dst_f32, 0, encoder).unwrap();
+    });
+
+    assert_eq!(dst.slice(..).map_blocking(&gpu.device).as_ref(), [0, 0, 2, 3]);
+    assert_eq!(dst_f32.slice(..).map_blocking(&gpu.device).as_ref(), [f32::from_bits(3), f32::from_bits(4)]);
+}
+
+#[test]
+fn copy_to_texture_checked(){
+    let mut gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    // Two layers of 64x4 texels, so that rows are 256 bytes wide.
+    let texture = TextureBuilder::new()
+        .clear([64, 4, 2])
+        .format(wgpu::TextureFormat::Rgba8Unorm)
+        .usage(wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::COPY_SRC)
+        .build_empty(&gpu.device);
+
+    // A region of 4x2x2 texels at an offset of 8 elements, with rows 256 bytes and images 2 rows
+    // apart requires 64 * 3 + 4 elements.
+    let data: Vec<u32> = (0..8 + 64 * 3 + 4).collect();
+    let src = BufferBuilder::<u32>::new()
+        .copy_src()
+        .build(&gpu.device, &data);
+
+    let mut texels = BufferBuilder::<u32>::new()
+        .read()
+        .copy_dst()
+        .build_empty(&gpu.device, 64 * 4 * 2);
+
+    let origin = wgpu::Origin3d{x: 1, y: 1, z: 0};
+    gpu.encode(|_, encoder|{
+        assert!(src.slice(8..).copy_to_texture(&texture, origin, [4, 2, 2], 16, encoder).is_err());
+        assert!(src.slice(8..).copy_to_texture(&texture, wgpu::Origin3d{x: 61, y: 1, z: 0}, [4, 2, 2], 256, encoder).is_err());
+        assert!(src.slice(8..).copy_to_texture(&texture, wgpu::Origin3d{x: u32::MAX, y: 1, z: 0}, [4, 2, 2], 256, encoder).is_err());
+        assert!(src.slice(12..).copy_to_texture(&texture, origin, [4, 2, 2], 256, encoder).is_err());
+
+        src.slice(8..).copy_to_texture(&texture, origin, [4, 2, 2], 256, encoder).unwrap();
+        texture.slice(.., .., ..).copy_to_buffer(encoder, &mut texels, 0);
+    });
+
+    let mut expected = vec![0; 64 * 4 * 2];
+    for z in 0..2{
+        for y in 0..2{
+            for x in 0..4{
+                let texel = z * 64 * 4 + (y + 1) * 64 + x + 1;
+                expected[texel] = (8 + z * 64 * 2 + y * 64 + x) as u32;
+            }
+        }
+    }
+    assert_eq!(texels.slice(..).map_blocking(&gpu.device).as_ref(), expected.as_slice());
+}
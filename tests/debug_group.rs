@@ -0,0 +1,79 @@
+use ewgpu::*;
+
+fn empty_pipeline(gpu: &GPUContext) -> ComputePipeline{
+    let cshader = ComputeShader::from_src(&gpu.device, "
+        #version 460
+        #if COMPUTE_SHADER
+
+        void main(){
+        }
+
+        #endif
+        ", None).unwrap();
+
+    let layout = pipeline_layout!(&gpu.device,
+        bind_groups: {},
+        push_constants: {}
+    );
+
+    ComputePipelineBuilder::new(&cshader)
+        .set_layout(&layout)
+        .build(&gpu.device)
+}
+
+#[test]
+fn debug_groups_balanced(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+    let pipeline = empty_pipeline(&gpu);
+
+    let mut encoder = gpu.device.create_command_encoder(&wgpu::CommandEncoderDescriptor{label: None});
+    let mut cpass = ComputePass::new(&mut encoder, None);
+    {
+        let mut cpass_ppl = cpass.set_pipeline(&pipeline);
+        assert!(cpass_ppl.pop_debug_group().is_err());
+
+        cpass_ppl.push_debug_group("outer");
+        cpass_ppl.push_debug_group("inner");
+        cpass_ppl.pop_debug_group().unwrap();
+        cpass_ppl.pop_debug_group().unwrap();
+        assert!(cpass_ppl.pop_debug_group().is_err());
+    }
+    cpass.end().unwrap();
+
+    gpu.queue.submit(Some(encoder.finish()));
+}
+
+#[test]
+fn debug_group_open_at_end(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+    let pipeline = empty_pipeline(&gpu);
+
+    let mut encoder = gpu.device.create_command_encoder(&wgpu::CommandEncoderDescriptor{label: None});
+    let mut cpass = ComputePass::new(&mut encoder, None);
+    cpass.set_pipeline(&pipeline).push_debug_group("unclosed");
+    assert!(cpass.end().is_err());
+
+    // The open group has been closed so the encoder is still valid.
+    gpu.queue.submit(Some(encoder.finish()));
+}
+
+#[test]
+fn debug_group_open_at_drop(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+    let pipeline = empty_pipeline(&gpu);
+
+    let mut encoder = gpu.device.create_command_encoder(&wgpu::CommandEncoderDescriptor{label: None});
+    {
+        let mut cpass = ComputePass::new(&mut encoder, None);
+        cpass.set_pipeline(&pipeline).push_debug_group("unclosed");
+    }
+
+    // Dropping the pass closes the open group and only logs an error.
+    gpu.queue.submit(Some(encoder.finish()));
+}
@@ -0,0 +1,128 @@
+use ewgpu::*;
+
+#[test]
+fn push_extend_and_grow(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let mut vec = GpuVec::<u32>::new(&gpu.device, wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::MAP_READ, None);
+    assert_eq!(vec.capacity(), 1);
+
+    vec.push(&gpu.device, &gpu.queue, 0);
+    vec.push(&gpu.device, &gpu.queue, 1);
+    assert_eq!(vec.capacity(), 2);
+    assert_eq!(vec.generation(), 1);
+
+    vec.extend_from_slice(&gpu.device, &gpu.queue, &[2, 3, 4]);
+    assert_eq!(vec.len(), 5);
+    assert_eq!(vec.capacity(), 5);
+    assert_eq!(vec.generation(), 2);
+
+    vec.truncate(3);
+    vec.push(&gpu.device, &gpu.queue, 7);
+    assert_eq!(vec.generation(), 2);
+
+    vec.resize(&gpu.device, &gpu.queue, 6, 9);
+    assert_eq!(vec.capacity(), 10);
+    gpu.queue.submit(None);
+
+    assert_eq!(vec.slice(..).map_blocking(&gpu.device).as_ref(), [0, 1, 2, 7, 9, 9]);
+
+    vec.clear();
+    assert!(vec.is_empty());
+}
+
+#[test]
+fn u16_elements_padded(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let mut vec = GpuVec::<u16>::new(&gpu.device, wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::MAP_READ, None);
+    // The capacity is rounded up to wgpu::COPY_BUFFER_ALIGNMENT bytes.
+    assert_eq!(vec.capacity(), 2);
+
+    vec.extend_from_slice(&gpu.device, &gpu.queue, &[1, 2, 3]);
+    assert_eq!(vec.len(), 3);
+    assert_eq!(vec.capacity(), 4);
+
+    vec.truncate(2);
+    vec.push(&gpu.device, &gpu.queue, 5);
+    gpu.queue.submit(None);
+
+    assert_eq!(vec.buffer().slice(..).map_blocking(&gpu.device).as_ref(), [1, 2, 5, 0]);
+}
+
+#[test]
+fn bound_gpu_vec_recreated_on_growth(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let mut bound = GpuVec::<u32>::new(&gpu.device, wgpu::BufferUsages::STORAGE, None)
+        .into_bound(&gpu.device);
+    let id = bound.id();
+    let bind_group: *const wgpu::BindGroup = bound.bind_group();
+
+    bound.extend_from_slice(&gpu.device, &gpu.queue, &[1, 2, 3]);
+    assert_ne!(bound.id(), id);
+    assert_eq!(bound.resource_ids()[0], bound.id());
+    // The BindGroup is recreated with the reallocated buffer when it is requested.
+    assert_ne!(bind_group, bound.bind_group() as *const _);
+}
+
+#[test]
+fn bound_gpu_vec_length(){
+    let mut gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let cshader = ComputeShader::from_src(&gpu.device, "
+        #version 460
+        #if COMPUTE_SHADER
+
+        layout(set = 0, binding = 0) buffer Data{
+            uint data[];
+        };
+        layout(set = 1, binding = 0) buffer Out{
+            uint len;
+        };
+
+        void main(){
+            len = data.length();
+        }
+
+        #endif
+        ", None).unwrap();
+
+    let layout = pipeline_layout!(&gpu.device,
+        bind_groups: {
+            data: GpuVec<u32>,
+            out: Buffer<u32>,
+        },
+        push_constants: {}
+    );
+
+    let cpipeline = ComputePipelineBuilder::new(&cshader)
+        .set_layout(&layout)
+        .build(&gpu.device);
+
+    let mut data = GpuVec::<u32>::with_capacity(&gpu.device, wgpu::BufferUsages::STORAGE, None, 8)
+        .into_bound(&gpu.device);
+    data.extend_from_slice(&gpu.device, &gpu.queue, &[1, 2, 3]);
+
+    let out = BufferBuilder::<u32>::new()
+        .storage().read()
+        .build_empty(&gpu.device, 1)
+        .into_bound(&gpu.device);
+
+    gpu.encode(|_, encoder|{
+        let mut cpass = ComputePass::new(encoder, None);
+        let mut cpass_ppl = cpass.set_pipeline(&cpipeline);
+        cpass_ppl.set_bind_group_slot(layout.slots.data, &data, &[]);
+        cpass_ppl.set_bind_group_slot(layout.slots.out, &out, &[]);
+        cpass_ppl.dispatch(1, 1, 1);
+    });
+    assert_eq!(out.slice(..).map_blocking(&gpu.device).as_ref(), [3]);
+}
@@ -0,0 +1,54 @@
+use ewgpu::*;
+
+#[test]
+fn kernel_run(){
+    let mut gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .set_limits(wgpu::Limits{
+            max_push_constant_size: 128,
+            ..Default::default()
+        })
+        .build();
+
+    let kernel = Kernel::<(Buffer<u32>, Buffer<u32>), u32>::new(&gpu.device, "
+        #version 460
+        #if COMPUTE_SHADER
+
+        layout(local_size_x = 64) in;
+
+        layout(set = 0, binding = 0) buffer InBuffer{
+            uint in_buf[];
+        };
+        layout(set = 0, binding = 1) buffer OutBuffer{
+            uint out_buf[];
+        };
+        layout(push_constant) uniform PushConstants{
+            uint factor;
+        };
+
+        void main(){
+            uint i = gl_GlobalInvocationID.x;
+            if (i < in_buf.length()){
+                out_buf[i] = in_buf[i] * factor;
+            }
+        }
+        #endif
+        ", None).unwrap();
+
+    let input: Vec<u32> = (0..100).collect();
+    let args = (
+        BufferBuilder::new()
+            .storage().write()
+            .build(&gpu.device, &input),
+        BufferBuilder::<u32>::new()
+            .storage().read()
+            .build_empty(&gpu.device, input.len()),
+    );
+
+    kernel.run(&mut gpu, &args, &3, input.len() as u32).unwrap();
+    kernel.run(&mut gpu, &args, &2, input.len() as u32).unwrap();
+    assert_eq!(kernel.cached_bind_groups(), 1);
+
+    let expected: Vec<u32> = input.iter().map(|x| x * 2).collect();
+    assert_eq!(args.1.slice(..).map_blocking(&gpu.device).as_ref(), &expected[..]);
+}
@@ -0,0 +1,120 @@
+use ewgpu::*;
+
+#[test]
+fn named_slots(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .set_limits(wgpu::Limits{
+            max_push_constant_size: 128,
+            ..Default::default()
+        })
+        .build();
+
+    let layout = pipeline_layout!(&gpu.device,
+        bind_groups: {
+            buffer: Buffer<u32>,
+            pair: BindGroup::<(Buffer<u32>, Buffer<f32>)>,
+            Buffer<u32>,
+            width: BoundUniform::<f32>,
+        },
+        push_constants: {
+            consts: [f32; 4] => wgpu::ShaderStages::FRAGMENT,
+            u32 => wgpu::ShaderStages::VERTEX,
+        }
+    );
+
+    let buffer: BindGroupSlot<Buffer<u32>> = layout.slots.buffer;
+    let pair: BindGroupSlot<(Buffer<u32>, Buffer<f32>)> = layout.slots.pair;
+    let width: BindGroupSlot<Uniform<f32>> = layout.slots.width;
+    let consts: PushConstantSlot<[f32; 4]> = layout.push.consts;
+
+    assert_eq!(buffer.index, 0);
+    assert_eq!(pair.index, 1);
+    assert_eq!(width.index, 3);
+    assert_eq!(consts.index, 0);
+    assert_eq!(layout.push_const_ranges.len(), 2);
+}
+
+pipeline_layout!{
+    pub struct StoredLayout{
+        bind_groups: {
+            buffer: Buffer<u32>,
+            Buffer<f32>,
+            uniform: Uniform<f32>,
+        },
+        push_constants: {
+            consts: [f32; 4] => wgpu::ShaderStages::COMPUTE,
+        }
+    }
+}
+
+struct Renderer{
+    layout: StoredLayout,
+}
+
+fn create_layout(device: &Device) -> StoredLayout{
+    StoredLayout::new(device)
+}
+
+#[test]
+fn named_slots_item(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .set_limits(wgpu::Limits{
+            max_push_constant_size: 128,
+            ..Default::default()
+        })
+        .build();
+
+    let renderer = Renderer{
+        layout: create_layout(&gpu.device),
+    };
+
+    let buffer: BindGroupSlot<Buffer<u32>> = renderer.layout.slots.buffer;
+    let uniform: StoredLayoutSlots = renderer.layout.slots;
+    let consts: PushConstantSlot<[f32; 4]> = renderer.layout.push.consts;
+
+    assert_eq!(buffer.index, 0);
+    assert_eq!(uniform.uniform.index, 2);
+    assert_eq!(consts.index, 0);
+    assert!(renderer.layout.push_const_slot::<[f32; 4]>(consts.index).is_ok());
+}
+
+#[test]
+fn set_bind_group_slot_with_bind_group(){
+    let mut gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .set_limits(wgpu::Limits{
+            max_push_constant_size: 128,
+            ..Default::default()
+        })
+        .build();
+
+    let layout = StoredLayout::new(&gpu.device);
+    let cshader = ComputeShader::from_src(&gpu.device, "
+        #version 460
+        #if COMPUTE_SHADER
+        layout(set = 0, binding = 0) buffer Out{
+            uint out_buf[];
+        };
+        void main(){
+            out_buf[0] = 1;
+        }
+        #endif
+        ", None).unwrap();
+    let cpipeline = ComputePipelineBuilder::new(&cshader)
+        .set_layout(&layout)
+        .build(&gpu.device);
+
+    let buffer = BufferBuilder::<u32>::new()
+        .storage().read()
+        .build_empty(&gpu.device, 1);
+    let bind_group = buffer.create_bind_group(&gpu.device);
+
+    gpu.encode(|_, encoder|{
+        let mut cpass = ComputePass::new(encoder, None);
+        let mut cpass_ppl = cpass.set_pipeline(&cpipeline);
+
+        cpass_ppl.set_bind_group_slot(layout.slots.buffer, &bind_group, &[]);
+    });
+}
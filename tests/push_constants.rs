@@ -32,7 +32,7 @@ fn push_const_compute(){
             let out_buf = BufferBuilder::<u32>::new()
                 .storage().read()
                 .build_empty(&gpu.device, 1)
-                .into_bound(wgpu::ShaderStages::all(), &gpu.device);
+                .into_bound(&gpu.device);
 
     /*
        let layout = PipelineLayoutBuilder::new()
@@ -53,16 +53,81 @@ fn push_const_compute(){
         .set_layout(&layout)
         .build(&gpu.device);
 
-    gpu.encode(|gpu, encoder|{
+    gpu.encode(|_, encoder|{
         {
             let mut cpass = ComputePass::new(encoder, None);
 
             let mut cpass_ppl = cpass.set_pipeline(&cpipeline);
 
             cpass_ppl.set_bind_group(0, &out_buf, &[]);
-            cpass_ppl.set_push_const(0, &(3 as u32));
-            cpass_ppl.dispatch(out_buf.len() as u32, 1, 1);
+            cpass_ppl.set_push_const(0, &3_u32).unwrap();
+            cpass_ppl.dispatch_for(out_buf.len() as u32).unwrap();
         }
     });
     assert_eq!(out_buf.slice(..).map_blocking(&gpu.device).as_ref(), [3]);
 }
+
+#[test]
+fn dispatch_for_workgroup_size(){
+    let mut gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .set_limits(wgpu::Limits{
+            max_push_constant_size: 128,
+            ..Default::default()
+        })
+        .build();
+    let cshader = ComputeShader::from_src_with_workgroup_size(&gpu.device, "
+            #version 460
+            #if COMPUTE_SHADER
+
+            layout(local_size_x = WORKGROUP_SIZE_X, local_size_y = WORKGROUP_SIZE_Y, local_size_z = WORKGROUP_SIZE_Z) in;
+
+            layout(set = 0, binding = 0) buffer OutBuffer{
+                uint out_buf[];
+            };
+            layout(push_constant) uniform PushConstants{
+                uint len;
+            };
+
+            void main(){
+                uint i = gl_GlobalInvocationID.x;
+                if (i < len){
+                    out_buf[i] = i;
+                }
+            }
+            #endif
+            ", [64, 1, 1], None).unwrap();
+    let out_buf = BufferBuilder::<u32>::new()
+        .storage().read()
+        .build_empty(&gpu.device, 100)
+        .into_bound(&gpu.device);
+
+    let layout = pipeline_layout!(&gpu.device,
+        bind_groups: {
+            buffer: Buffer<u32>,
+        },
+        push_constants: {
+            len: u32 => wgpu::ShaderStages::COMPUTE,
+        }
+    );
+
+    let cpipeline = ComputePipelineBuilder::new(&cshader)
+        .set_layout(&layout)
+        .build(&gpu.device);
+
+    assert_eq!(cpipeline.workgroup_size, Some([64, 1, 1]));
+    assert_eq!(cpipeline.workgroup_count([100, 1, 1]).unwrap(), [2, 1, 1]);
+    assert!(cpipeline.workgroup_count([u32::MAX, 1, 1]).is_err());
+
+    gpu.encode(|_gpu, encoder|{
+        let mut cpass = ComputePass::new(encoder, None);
+
+        let mut cpass_ppl = cpass.set_pipeline(&cpipeline);
+
+        cpass_ppl.set_bind_group_slot(layout.slots.buffer, &out_buf, &[]);
+        cpass_ppl.set_push_const_slot(layout.push.len, &(out_buf.len() as u32));
+        cpass_ppl.dispatch_for(out_buf.len() as u32).unwrap();
+    });
+    let expected: Vec<u32> = (0..100).collect();
+    assert_eq!(out_buf.slice(..).map_blocking(&gpu.device).as_ref(), &expected[..]);
+}
@@ -0,0 +1,93 @@
+use ewgpu::*;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, bytemuck::Pod, bytemuck::Zeroable)]
+#[derive(Vert)]
+struct Vert2{
+    #[location = 0]
+    pos: [f32; 2],
+}
+
+#[test]
+fn bundle_draws_mesh(){
+    let mut gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let program = ShaderProgram::from_src(&gpu.device, "
+        #version 460
+        #if VERTEX_SHADER
+
+        layout(location = 0) in vec2 i_pos;
+
+        void main(){
+            gl_Position = vec4(i_pos, 0.0, 1.0);
+        }
+
+        #elif FRAGMENT_SHADER
+
+        layout(location = 0) out vec4 o_color;
+
+        void main(){
+            o_color = vec4(1.0);
+        }
+
+        #endif
+        ", None).unwrap();
+
+    let layout = pipeline_layout!(&gpu.device,
+        bind_groups: {},
+        push_constants: {}
+    );
+
+    let pipeline = RenderPipelineBuilder::from_program(&program).unwrap()
+        .set_layout(&layout)
+        .push_vert_layout(Vert2::buffer_layout())
+        .push_target_replace(wgpu::TextureFormat::Rgba8Unorm)
+        .build(&gpu.device);
+
+    // A quad covering the left half of the target.
+    let mesh = IndexedMesh::<Vert2, u16>::new(&gpu.device, None, &[
+        Vert2{pos: [-1., -1.]},
+        Vert2{pos: [0., -1.]},
+        Vert2{pos: [0., 1.]},
+        Vert2{pos: [-1., 1.]},
+    ], &[0, 1, 2, 2, 3, 0]);
+
+    let mut bundle_encoder = RenderBundleBuilder::new()
+        .push_color_format(wgpu::TextureFormat::Rgba8Unorm)
+        .begin(&gpu.device);
+    {
+        let mut bundle_ppl = bundle_encoder.set_pipeline(&pipeline);
+        // No index buffer has been set yet.
+        assert!(bundle_ppl.draw_indexed(.., 0, 0..1).is_err());
+        assert!(bundle_ppl.draw_indexed(..=u32::MAX, 0, 0..1).is_err());
+        bundle_ppl.draw_mesh(&mesh, 0..1);
+    }
+    let bundle = bundle_encoder.finish(None);
+
+    let target = TextureBuilder::new()
+        .clear([64, 1])
+        .format(wgpu::TextureFormat::Rgba8Unorm)
+        .usage(wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC)
+        .build_empty(&gpu.device);
+
+    let mut texels = BufferBuilder::<u32>::new()
+        .read()
+        .copy_dst()
+        .build_empty(&gpu.device, 64);
+
+    gpu.encode(|_, encoder|{
+        {
+            let mut rpass = RenderPassBuilder::new()
+                .push_color_attachment(target.view.color_attachment_clear())
+                .begin(encoder, None);
+            rpass.execute_bundles([&bundle]);
+        }
+        target.slice(.., .., ..).copy_to_buffer(encoder, &mut texels, 0);
+    });
+
+    let texels = texels.slice(..).map_blocking(&gpu.device);
+    assert!(texels[..32].iter().all(|texel| *texel == 0xffffffff));
+    assert!(texels[32..].iter().all(|texel| *texel == 0));
+}
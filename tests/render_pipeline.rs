@@ -0,0 +1,207 @@
+use ewgpu::*;
+
+#[test]
+fn vertex_only_renders_depth(){
+    let mut gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    // A quad covering the left half of the target at depth 0.5.
+    let program = ShaderProgram::from_src(&gpu.device, "
+        #version 460
+        #if VERTEX_SHADER
+
+        const vec2 positions[6] = vec2[](
+            vec2(-1.0, -1.0), vec2(0.0, -1.0), vec2(0.0, 1.0),
+            vec2(0.0, 1.0), vec2(-1.0, 1.0), vec2(-1.0, -1.0)
+        );
+
+        void main(){
+            gl_Position = vec4(positions[gl_VertexIndex], 0.5, 1.0);
+        }
+
+        #endif
+        ", None).unwrap();
+
+    let layout = pipeline_layout!(&gpu.device,
+        bind_groups: {},
+        push_constants: {}
+    );
+
+    assert!(program.fragment.is_none());
+
+    let without_depth = RenderPipelineBuilder::from_program(&program).unwrap()
+        .set_layout(&layout)
+        .try_build(&gpu.device);
+    assert!(without_depth.is_err());
+
+    let pipeline = RenderPipelineBuilder::from_program(&program).unwrap()
+        .set_layout(&layout)
+        .set_depth_stencil_less32()
+        .try_build(&gpu.device)
+        .unwrap();
+    assert!(pipeline.targets.is_empty());
+
+    let depth = TextureBuilder::new()
+        .clear([64, 1])
+        .format(wgpu::TextureFormat::Depth32Float)
+        .usage(wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC)
+        .build_empty(&gpu.device);
+
+    let mut texels = BufferBuilder::<f32>::new()
+        .read()
+        .copy_dst()
+        .build_empty(&gpu.device, 64);
+
+    gpu.encode(|_, encoder|{
+        {
+            let mut rpass = RenderPassBuilder::new()
+                .set_depth_stencil_attachment(depth.view.depth_attachment_clear())
+                .begin(encoder, None);
+            let mut rpass_ppl = rpass.set_pipeline(&pipeline);
+            rpass_ppl.draw(0..6, 0..1);
+        }
+        depth.slice(.., .., ..).copy_to_buffer(encoder, &mut texels, 0);
+    });
+
+    let texels = texels.slice(..).map_blocking(&gpu.device);
+    assert!(texels[..32].iter().all(|texel| *texel == 0.5));
+    assert!(texels[32..].iter().all(|texel| *texel == 1.0));
+}
+
+#[test]
+fn pipeline_cache_deduplicates(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let program = ShaderProgram::from_src(&gpu.device, "
+        #version 460
+        #if VERTEX_SHADER
+
+        void main(){
+            gl_Position = vec4(0.0, 0.0, 0.5, 1.0);
+        }
+
+        #elif FRAGMENT_SHADER
+
+        layout(location = 0) out vec4 o_color;
+
+        void main(){
+            o_color = vec4(1.0);
+        }
+
+        #endif
+        ", None).unwrap();
+
+    let layout = pipeline_layout!(&gpu.device,
+        bind_groups: {},
+        push_constants: {}
+    );
+
+    let build = |format|{
+        gpu.pipeline_cache.render(&gpu.device, RenderPipelineBuilder::from_program(&program).unwrap()
+            .set_layout(&layout)
+            .push_target_replace(format))
+    };
+
+    let a = build(wgpu::TextureFormat::Rgba8Unorm);
+    let b = build(wgpu::TextureFormat::Rgba8Unorm);
+    let c = build(wgpu::TextureFormat::Bgra8Unorm);
+
+    assert!(std::sync::Arc::ptr_eq(&a, &b));
+    assert!(!std::sync::Arc::ptr_eq(&a, &c));
+
+    let stats = gpu.pipeline_cache.stats();
+    assert_eq!(stats.hits, 1);
+    assert_eq!(stats.misses, 2);
+    assert_eq!(stats.render_pipelines, 2);
+    assert_eq!(stats.compute_pipelines, 0);
+}
+
+#[test]
+fn pipeline_cache_deduplicates_identical_modules(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let src = "
+        #version 460
+        #if VERTEX_SHADER
+
+        void main(){
+            gl_Position = vec4(0.0, 0.0, 0.5, 1.0);
+        }
+
+        #elif FRAGMENT_SHADER
+
+        layout(location = 0) out vec4 o_color;
+
+        void main(){
+            o_color = vec4(1.0);
+        }
+
+        #endif
+        ";
+
+    let build = ||{
+        let program = ShaderProgram::from_src(&gpu.device, src, None).unwrap();
+        let layout = pipeline_layout!(&gpu.device,
+            bind_groups: {},
+            push_constants: {}
+        );
+        gpu.pipeline_cache.render(&gpu.device, RenderPipelineBuilder::from_program(&program).unwrap()
+            .set_layout(&layout)
+            .push_target_replace(wgpu::TextureFormat::Rgba8Unorm))
+    };
+
+    let a = build();
+    let b = build();
+
+    assert!(std::sync::Arc::ptr_eq(&a, &b));
+    assert_eq!(gpu.pipeline_cache.stats().hits, 1);
+
+    assert_eq!(gpu.pipeline_cache.evict_unused(), 0);
+    drop(a);
+    drop(b);
+    assert_eq!(gpu.pipeline_cache.evict_unused(), 1);
+    assert_eq!(gpu.pipeline_cache.stats().render_pipelines, 0);
+}
+
+#[test]
+fn pipeline_cache_try_compute(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let cshader = ComputeShader::from_src(&gpu.device, "
+        #version 460
+        #if COMPUTE_SHADER
+
+        layout(local_size_x = 64) in;
+
+        void main(){
+        }
+
+        #endif
+        ", None).unwrap();
+
+    let layout = pipeline_layout!(&gpu.device,
+        bind_groups: {},
+        push_constants: {}
+    );
+
+    let invalid = gpu.pipeline_cache.try_compute(&gpu.device, ComputePipelineBuilder::new(&cshader)
+        .set_layout(&layout)
+        .set_workgroup_size([0, 1, 1]));
+    assert!(invalid.is_err());
+
+    let pipeline = gpu.pipeline_cache.try_compute(&gpu.device, ComputePipelineBuilder::new(&cshader)
+        .set_layout(&layout))
+        .unwrap();
+    assert_eq!(pipeline.workgroup_size, Some([64, 1, 1]));
+
+    let stats = gpu.pipeline_cache.stats();
+    assert_eq!(stats.misses, 1);
+    assert_eq!(stats.compute_pipelines, 1);
+}
@@ -0,0 +1,79 @@
+use ewgpu::*;
+
+#[test]
+fn mapped_at_creation(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let buffer = BufferBuilder::<u32>::new()
+        .read()
+        .build_mapped_at_creation(&gpu.device, 4, |view|{
+            view.copy_from_slice(&[0, 1, 2, 3]);
+        });
+
+    assert_eq!(buffer.slice(..).try_map_blocking(&gpu.device).unwrap().as_ref(), [0, 1, 2, 3]);
+}
+
+#[test]
+fn staging_pool_reuses_chunks(){
+    let mut gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let buffer = BufferBuilder::<u32>::new()
+        .read()
+        .copy_dst()
+        .build_empty(&gpu.device, 8);
+
+    let mut pool = StagingPool::new(256);
+
+    let mut result = Ok(());
+    gpu.encode(|gpu, encoder|{
+        assert!(pool.write_buffer(&gpu.device, encoder, &buffer, usize::MAX, &[0]).is_err());
+        result = pool.write_buffer(&gpu.device, encoder, &buffer, 0, &[0, 1, 2, 3])
+            .and_then(|_| pool.write_buffer(&gpu.device, encoder, &buffer, 4, &[4, 5, 6, 7]));
+        pool.finish();
+    });
+    result.unwrap();
+    assert_eq!(pool.stats().closed, 1);
+
+    pool.recall();
+    gpu.device.poll(wgpu::Maintain::Wait);
+    pool.poll_mapping().unwrap();
+    assert_eq!(pool.stats().free, 1);
+
+    assert_eq!(buffer.slice(..).try_map_blocking(&gpu.device).unwrap().as_ref(), [0, 1, 2, 3, 4, 5, 6, 7]);
+
+    let mut result = Ok(());
+    gpu.encode(|gpu, encoder|{
+        result = pool.write_buffer(&gpu.device, encoder, &buffer, 0, &[8]);
+        pool.finish();
+    });
+    result.unwrap();
+    assert_eq!(pool.stats().closed, 1);
+    assert_eq!(pool.stats().size, 256);
+
+    pool.recall();
+    gpu.device.poll(wgpu::Maintain::Wait);
+    pool.poll_mapping().unwrap();
+    pool.trim(256);
+    assert_eq!(pool.stats().free, 1);
+    pool.trim(0);
+    assert_eq!(pool.stats(), StagingPoolStats::default());
+}
+
+#[test]
+fn staging_pool_requires_copy_dst(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let buffer = BufferBuilder::<u32>::new()
+        .read()
+        .build_empty(&gpu.device, 4);
+
+    let mut pool = StagingPool::new(256);
+    let mut encoder = gpu.device.create_command_encoder(&wgpu::CommandEncoderDescriptor{label: None});
+    assert!(pool.write_buffer(&gpu.device, &mut encoder, &buffer, 0, &[0]).is_err());
+}
@@ -0,0 +1,74 @@
+use ewgpu::*;
+
+#[test]
+fn texture_view_sample_type(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let texture = TextureBuilder::new()
+        .clear([4, 4])
+        .format(wgpu::TextureFormat::Rgba8Uint)
+        .build_empty(&gpu.device);
+
+    assert!(texture.view().build::<kind::Float, dim::D2>().is_err());
+    assert!(texture.view().build::<kind::Uint, dim::D3>().is_err());
+    assert!(texture.view().build_sampled::<kind::Uint, dim::D2>().is_ok());
+
+    let depth = TextureBuilder::new()
+        .clear([4, 4])
+        .format(wgpu::TextureFormat::Depth32Float)
+        .build_empty(&gpu.device);
+
+    assert!(depth.view().build::<kind::Depth, dim::D2>().is_ok());
+    assert!(depth.view().build::<kind::UnfilterableFloat, dim::D2>().is_ok());
+    assert!(depth.view().build::<kind::Float, dim::D2>().is_err());
+}
+
+#[test]
+fn storage_texture_view(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let texture = TextureBuilder::new()
+        .clear([30, 20])
+        .format(wgpu::TextureFormat::Rgba8Unorm)
+        .usage(wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::COPY_SRC)
+        .build_empty(&gpu.device);
+
+    assert!(texture.view().build_storage::<format::Rgba32Float, access::WriteOnly, dim::D2>().is_err());
+
+    let storage = texture.view().build_storage::<format::Rgba8Unorm, access::WriteOnly, dim::D2>().unwrap();
+    assert_eq!(storage.size, wgpu::Extent3d{width: 30, height: 20, depth_or_array_layers: 1});
+
+    let sampled_only = TextureBuilder::new()
+        .clear([4, 4])
+        .format(wgpu::TextureFormat::Rgba8Unorm)
+        .build_empty(&gpu.device);
+    assert!(sampled_only.view().build_storage::<format::Rgba8Unorm, access::ReadOnly, dim::D2>().is_err());
+}
+
+#[test]
+fn texture_mip_levels_and_bound(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let texture = TextureBuilder::new()
+        .clear([16, 16])
+        .mip_level_count(5)
+        .format(wgpu::TextureFormat::Rgba8Unorm)
+        .usage(wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::STORAGE_BINDING)
+        .build_empty(&gpu.device);
+    assert_eq!(texture.mip_level_count, 5);
+
+    assert!(texture.view().build_storage::<format::Rgba8Unorm, access::WriteOnly, dim::D2>().is_err());
+    assert!(texture.view().mip_levels(4..5).build_storage::<format::Rgba8Unorm, access::WriteOnly, dim::D2>().is_ok());
+
+    let mut builder = TextureBuilder::new()
+        .clear([4, 4])
+        .format(wgpu::TextureFormat::Rgba8Uint);
+    assert!(builder.bound::<kind::Float, dim::D2>(&gpu.device, &gpu.queue).is_err());
+    assert!(builder.bound::<kind::Uint, dim::D2>(&gpu.device, &gpu.queue).is_ok());
+}